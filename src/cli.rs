@@ -0,0 +1,84 @@
+pub mod commands;
+
+use alloy::primitives::B256;
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use polymarket_client_sdk::types::Decimal;
+
+#[derive(Parser)]
+#[command(version, about = "Polymarket liquidity reward market maker")]
+pub struct Cli {
+    #[arg(long, global = true, default_value = "config.toml")]
+    pub config: String,
+    #[arg(
+        long,
+        global = true,
+        help = "Start without the confirmation prompt, for unattended deployments"
+    )]
+    pub acknowledge_risk: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    #[command(about = "Quote the configured markets (default)")]
+    Run,
+    #[command(about = "Check config.toml and resolve every configured market")]
+    ValidateConfig {
+        #[arg(long, help = "Only parse the file, skip resolving markets on Gamma")]
+        offline: bool,
+    },
+    #[command(about = "Cancel all open orders, or only those of one market")]
+    CancelAll {
+        #[arg(long, help = "Condition id of the market to cancel")]
+        market: Option<B256>,
+    },
+    #[command(about = "Show open orders and positions")]
+    Status,
+    #[command(about = "Rank reward markets by expected reward per dollar")]
+    Scan {
+        #[arg(long, default_value_t = 20)]
+        top: usize,
+        #[arg(long, default_value = "50")]
+        size: Decimal,
+        #[arg(long, help = "Print config.toml entries instead of a table")]
+        toml: bool,
+    },
+    #[command(about = "Show positions of the funder address")]
+    Positions,
+    #[command(about = "Replay price history of the configured markets against the quoting logic")]
+    Backtest {
+        #[arg(
+            long,
+            default_value = "1w",
+            help = "Price history interval (1d, 1w, 1m, max)"
+        )]
+        interval: String,
+        #[arg(long, default_value_t = 60, help = "Minutes between price points")]
+        fidelity: u32,
+    },
+    #[command(about = "Derive the CLOB API key of the configured wallet")]
+    DeriveApiKey,
+}
+
+impl Cli {
+    pub async fn run(self) -> Result<()> {
+        match self.command.unwrap_or(Command::Run) {
+            Command::Run => commands::run(&self.config, self.acknowledge_risk).await,
+            Command::ValidateConfig { offline } => {
+                commands::validate_config(&self.config, offline).await
+            }
+            Command::CancelAll { market } => commands::cancel_all(market).await,
+            Command::Status => commands::status(&self.config).await,
+            Command::Scan { top, size, toml } => {
+                commands::scan(&self.config, top, size, toml).await
+            }
+            Command::Positions => commands::positions(&self.config).await,
+            Command::Backtest { interval, fidelity } => {
+                commands::backtest(&self.config, &interval, fidelity).await
+            }
+            Command::DeriveApiKey => commands::derive_api_key().await,
+        }
+    }
+}
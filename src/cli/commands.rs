@@ -0,0 +1,255 @@
+use crate::engine::backtest::{print_report, simulate};
+use crate::engine::bot_manager::BotManager;
+use crate::engine::scanner::{ScanParams, print_table, print_toml, scan_markets};
+use crate::infra::config::{HttpConfig, MarketConfigs};
+use crate::infra::{ClobRestClient, ConfigParams, DataApiClient, GammaClient, SigningUtils};
+use crate::market_logic::Market;
+use alloy::primitives::{Address, B256};
+use anyhow::{Context, Result, bail};
+use chrono::TimeDelta;
+use polymarket_client_sdk::clob::types::request::{CancelMarketOrderRequest, OrdersRequest};
+use polymarket_client_sdk::types::Decimal;
+use std::io;
+use std::io::{IsTerminal, Write};
+
+const MAX_CLOCK_SKEW: TimeDelta = TimeDelta::seconds(5);
+
+pub async fn run(config_path: &str, acknowledge_risk: bool) -> Result<()> {
+    println!("Loading config...");
+    let config_params = ConfigParams::new(config_path)?;
+    confirm_risk(
+        config_path,
+        acknowledge_risk || config_params.market_configs.acknowledge_risk,
+    )?;
+    println!("Creating signing utils...");
+    let signing_utils =
+        SigningUtils::new_client(&config_params.private_key, &config_params.funder_address).await?;
+
+    if config_params
+        .market_configs
+        .markets
+        .iter()
+        .any(|market_config| market_config.gtd.is_some())
+    {
+        let clock_skew = signing_utils.clock_skew().await?;
+        println!("Clock skew against server: {}s", clock_skew.num_seconds());
+        if clock_skew.abs() > MAX_CLOCK_SKEW {
+            bail!(
+                "Clock skew of {}s is too large for GTD orders",
+                clock_skew.num_seconds()
+            );
+        }
+    }
+
+    let gamma_client = GammaClient::new(
+        &config_params.market_configs.gamma,
+        &config_params.market_configs.http,
+    )?;
+    let clob_client = ClobRestClient::new(&config_params.market_configs.http)?;
+
+    println!("Initializing bot manager");
+    let bot_manager =
+        BotManager::new(config_params, signing_utils, gamma_client, clob_client).await;
+    println!("Initialization complete\nQuoting markets...");
+    bot_manager.run();
+    Ok(())
+}
+
+fn confirm_risk(config_path: &str, acknowledged: bool) -> Result<()> {
+    println!("This project is under active development and not suitable for use with real funds.");
+    if acknowledged {
+        println!("Risk acknowledged, quoting on Polygon mainnet");
+        return Ok(());
+    }
+    if !io::stdin().is_terminal() {
+        bail!(
+            "Refusing to quote on Polygon mainnet without confirmation: pass --acknowledge-risk or set acknowledge_risk = true in {config_path}"
+        );
+    }
+    println!("Press enter to continue anyway");
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .context("Failed to read line")?;
+    Ok(())
+}
+
+pub async fn validate_config(config_path: &str, offline: bool) -> Result<()> {
+    let market_configs = MarketConfigs::load_config(config_path)?;
+    println!(
+        "{config_path} is valid: {} markets, {} groups, allocator {}",
+        market_configs.markets.len(),
+        market_configs.groups.len(),
+        if market_configs.allocator.is_some() {
+            "enabled"
+        } else {
+            "disabled"
+        }
+    );
+    if offline {
+        return Ok(());
+    }
+
+    let gamma_client = GammaClient::new(&market_configs.gamma, &market_configs.http)?;
+    let mut failures = 0;
+    for market_config in market_configs.markets {
+        let identifier = market_config.identifier()?.to_string();
+        match Market::from_config(market_config, &gamma_client).await {
+            Ok(markets) => {
+                for (market, market_id, market_slug) in markets {
+                    println!(
+                        "{identifier}: {market_slug} ({market_id}) tick size {}, neg risk {}, group {}",
+                        market.tick_size,
+                        market.neg_risk,
+                        market.group.as_deref().unwrap_or("-")
+                    );
+                }
+            }
+            Err(e) => {
+                failures += 1;
+                eprintln!("{identifier}: {e}");
+            }
+        }
+    }
+    if failures > 0 {
+        bail!("{failures} configured markets could not be resolved");
+    }
+    Ok(())
+}
+
+pub async fn cancel_all(market: Option<B256>) -> Result<()> {
+    let signing_utils = signing_utils().await?;
+    match market {
+        Some(market_id) => {
+            let mut cancel_market_orders_request = CancelMarketOrderRequest::default();
+            cancel_market_orders_request.market = Some(market_id);
+            signing_utils
+                .client
+                .cancel_market_orders(&cancel_market_orders_request)
+                .await
+                .with_context(|| format!("Failed to cancel orders for market: {market_id}"))?;
+            println!("Cancelled all orders for market: {market_id}");
+        }
+        None => {
+            signing_utils
+                .client
+                .cancel_all_orders()
+                .await
+                .context("Failed to cancel all orders")?;
+            println!("Cancelled all orders");
+        }
+    }
+    Ok(())
+}
+
+pub async fn status(config_path: &str) -> Result<()> {
+    let http_config = MarketConfigs::load_config(config_path)?.http;
+    let signing_utils = signing_utils().await?;
+    let open_orders = signing_utils
+        .client
+        .orders(&OrdersRequest::default(), None)
+        .await
+        .context("Failed to fetch open orders")?;
+    println!("Open orders: {}", open_orders.data.len());
+    for open_order in open_orders.data {
+        println!(
+            "{} {:?} {} @ {} matched {}  {}",
+            open_order.id,
+            open_order.side,
+            open_order.original_size,
+            open_order.price,
+            open_order.size_matched,
+            open_order.market
+        );
+    }
+    println!();
+    print_positions(&http_config, signing_utils.funder_address).await
+}
+
+pub async fn scan(
+    config_path: &str,
+    top: usize,
+    order_size: Decimal,
+    emit_toml: bool,
+) -> Result<()> {
+    let market_configs = MarketConfigs::load_config(config_path)?;
+    eprintln!("Scanning reward markets...");
+    let gamma_client = GammaClient::new(&market_configs.gamma, &market_configs.http)?;
+    let clob_client = ClobRestClient::new(&market_configs.http)?;
+    let scanned_markets =
+        scan_markets(&gamma_client, &clob_client, &ScanParams { order_size, top }).await?;
+    if emit_toml {
+        print_toml(&scanned_markets);
+    } else {
+        print_table(&scanned_markets);
+    }
+    Ok(())
+}
+
+pub async fn positions(config_path: &str) -> Result<()> {
+    let http_config = MarketConfigs::load_config(config_path)?.http;
+    let (funder_address, _) = ConfigParams::load_env_vars()?;
+    let funder_address: Address = funder_address.parse().context("Invalid funder address")?;
+    print_positions(&http_config, funder_address).await
+}
+
+pub async fn backtest(config_path: &str, interval: &str, fidelity: u32) -> Result<()> {
+    let market_configs = MarketConfigs::load_config(config_path)?;
+    let gamma_client = GammaClient::new(&market_configs.gamma, &market_configs.http)?;
+    let clob_client = ClobRestClient::new(&market_configs.http)?;
+    for market_config in market_configs.markets {
+        let markets = match Market::from_config(market_config, &gamma_client).await {
+            Ok(markets) => markets,
+            Err(e) => {
+                eprintln!("Skipping market: {e}");
+                continue;
+            }
+        };
+        for (mut market, _, market_slug) in markets {
+            let price_points = clob_client
+                .price_history(market.token_ids.buy_token, interval, fidelity)
+                .await?;
+            let report = simulate(&mut market, &price_points);
+            print_report(&market_slug, &report);
+        }
+    }
+    Ok(())
+}
+
+pub async fn derive_api_key() -> Result<()> {
+    let (_, private_key) = ConfigParams::load_env_vars()?;
+    let credentials = SigningUtils::derive_api_key(&private_key).await?;
+    println!("API key: {}", credentials.key());
+    println!("Secret: {}", credentials.secret());
+    println!("Passphrase: {}", credentials.passphrase());
+    Ok(())
+}
+
+async fn signing_utils() -> Result<SigningUtils> {
+    let (funder_address, private_key) = ConfigParams::load_env_vars()?;
+    SigningUtils::new_client(&private_key, &funder_address).await
+}
+
+async fn print_positions(http_config: &HttpConfig, funder_address: Address) -> Result<()> {
+    let data_api_client = DataApiClient::new(http_config)?;
+    let positions = data_api_client.positions(funder_address).await?;
+    println!("Positions: {}", positions.len());
+    println!(
+        "{:>10} {:>8} {:>8} {:>10}  market",
+        "size", "avg", "price", "pnl"
+    );
+    for position in positions {
+        println!(
+            "{:>10.2} {:>8.3} {:>8.3} {:>10.2}  {} {} ({})",
+            position.size,
+            position.avg_price,
+            position.cur_price,
+            position.cash_pnl,
+            position.slug,
+            position.outcome,
+            position.condition_id
+        );
+    }
+    Ok(())
+}
@@ -1 +1,5 @@
+pub mod allocator;
+pub mod backtest;
 pub mod bot_manager;
+pub mod reloader;
+pub mod scanner;
@@ -0,0 +1,208 @@
+use crate::engine::scanner::{ScanParams, ScannedMarket, scan_markets};
+use crate::infra::config::{AllocatorConfig, MarketConfig, MarketSelector};
+use crate::infra::{ClobRestClient, GammaClient};
+use crate::market_logic::Market;
+use crate::types::channel_types::{Allocation, ChannelData, ChannelMessage, ManagerMessage};
+use ahash::{AHashMap, AHashSet};
+use alloy::primitives::B256;
+use crossfire::MAsyncTx;
+use polymarket_client_sdk::types::Decimal;
+use std::time::Duration;
+
+pub struct MarketAllocation {
+    pub condition_id: B256,
+    pub config: MarketConfig,
+}
+
+pub fn allocate(
+    scanned_markets: &[ScannedMarket],
+    allocator_config: &AllocatorConfig,
+    excluded_market_ids: &AHashSet<B256>,
+) -> Vec<MarketAllocation> {
+    let selected_markets: Vec<&ScannedMarket> = scanned_markets
+        .iter()
+        .filter(|scanned_market| {
+            !excluded_market_ids.contains(&scanned_market.condition_id)
+                && scanned_market.score() > allocator_config.min_score
+        })
+        .take(allocator_config.max_markets)
+        .collect();
+    let total_score: Decimal = selected_markets
+        .iter()
+        .map(|scanned_market| scanned_market.score())
+        .sum();
+    if total_score <= Decimal::from(0) {
+        return Vec::new();
+    }
+
+    selected_markets
+        .into_iter()
+        .filter_map(|scanned_market| {
+            let capital = allocator_config.total_capital * scanned_market.score() / total_score;
+            let capital_per_share = Decimal::from(1) - scanned_market.spread * Decimal::from(2);
+            if capital_per_share <= Decimal::from(0) {
+                return None;
+            }
+            let order_size = (capital / capital_per_share).round_dp(2);
+            if order_size < scanned_market.min_size {
+                return None;
+            }
+            let mut config = MarketConfig::new(
+                scanned_market.event_slug.clone(),
+                order_size,
+                scanned_market.spread,
+                order_size,
+            );
+            config.market = MarketSelector::Slug(scanned_market.market_slug.clone());
+            config.primary_outcome = Some(scanned_market.primary_outcome.clone());
+            config.reward_max_spread = Some(scanned_market.max_spread);
+            config.reward_min_size = Some(scanned_market.min_size);
+            Some(MarketAllocation {
+                condition_id: scanned_market.condition_id,
+                config,
+            })
+        })
+        .collect()
+}
+
+pub async fn run_allocator(
+    gamma_client: GammaClient,
+    clob_client: ClobRestClient,
+    allocator_config: AllocatorConfig,
+    static_market_ids: AHashSet<B256>,
+    tx: MAsyncTx<ManagerMessage>,
+) {
+    let mut allocated_sizes: AHashMap<B256, Decimal> = AHashMap::new();
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(allocator_config.rescan_interval_secs));
+    let scan_params = ScanParams {
+        order_size: allocator_config.total_capital
+            / Decimal::from(allocator_config.max_markets.max(1)),
+        top: allocator_config.max_markets + static_market_ids.len(),
+    };
+
+    loop {
+        interval.tick().await;
+        let scanned_markets = match scan_markets(&gamma_client, &clob_client, &scan_params).await {
+            Ok(scanned_markets) => scanned_markets,
+            Err(e) => {
+                eprintln!("Allocator scan failed: {e}");
+                continue;
+            }
+        };
+        let allocations = allocate(&scanned_markets, &allocator_config, &static_market_ids);
+        let target_market_ids: AHashSet<B256> = allocations
+            .iter()
+            .map(|allocation| allocation.condition_id)
+            .collect();
+
+        let mut updates = Vec::new();
+        allocated_sizes.retain(|market_id, _| {
+            let keep = target_market_ids.contains(market_id);
+            if !keep {
+                updates.push((*market_id, Allocation::Retire));
+            }
+            keep
+        });
+        for allocation in allocations {
+            let order_size = allocation.config.order_size;
+            match allocated_sizes.get(&allocation.condition_id) {
+                Some(allocated_size) if *allocated_size == order_size => continue,
+                Some(_) => updates.push((
+                    allocation.condition_id,
+                    Allocation::Resize {
+                        order_size,
+                        max_exposure: allocation.config.max_exposure,
+                    },
+                )),
+                None => match Market::from_config(allocation.config, &gamma_client).await {
+                    Ok(markets) => {
+                        for (market, market_id, _) in markets {
+                            updates.push((market_id, Allocation::Add(Box::new(market))));
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Allocator market creation failed: {e}");
+                        continue;
+                    }
+                },
+            }
+            allocated_sizes.insert(allocation.condition_id, order_size);
+        }
+
+        for (market_id, allocation) in updates {
+            let channel_message = ChannelMessage {
+                market_id,
+                channel_data: ChannelData::Allocation(allocation),
+            };
+            if let Err(e) = tx.send(channel_message.into()).await {
+                println!("Error sending allocation through data channel. Error: {e}");
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scanned_market(id: u8, reward_per_dollar: Decimal, min_size: Decimal) -> ScannedMarket {
+        ScannedMarket {
+            condition_id: B256::repeat_byte(id),
+            event_slug: format!("event-{id}"),
+            market_slug: format!("market-{id}"),
+            question: String::new(),
+            primary_outcome: "Yes".to_string(),
+            daily_rate: Decimal::from(0),
+            max_spread: Decimal::new(3, 2),
+            min_size,
+            midpoint: Decimal::new(5, 1),
+            spread: Decimal::new(2, 2),
+            order_size: Decimal::from(0),
+            competition: Decimal::from(0),
+            reward_per_dollar,
+            volatility: Decimal::from(0),
+        }
+    }
+
+    #[test]
+    fn allocation_splits_capital_by_score_among_eligible_markets() {
+        let scanned_markets = vec![
+            scanned_market(1, Decimal::from(3), Decimal::from(5)),
+            scanned_market(2, Decimal::from(2), Decimal::from(5)),
+            scanned_market(3, Decimal::from(1), Decimal::from(5)),
+            scanned_market(4, Decimal::new(8, 1), Decimal::from(50)),
+            scanned_market(5, Decimal::new(5, 1), Decimal::from(5)),
+        ];
+        let mut allocator_config = AllocatorConfig {
+            total_capital: Decimal::from(100),
+            max_markets: 3,
+            rescan_interval_secs: 3600,
+            min_score: Decimal::new(6, 1),
+        };
+        let excluded_market_ids = AHashSet::from_iter([B256::repeat_byte(2)]);
+
+        let allocations = allocate(&scanned_markets, &allocator_config, &excluded_market_ids);
+        let sizes: Vec<(B256, Decimal)> = allocations
+            .iter()
+            .map(|allocation| (allocation.condition_id, allocation.config.order_size))
+            .collect();
+        assert_eq!(
+            sizes,
+            vec![
+                (B256::repeat_byte(1), Decimal::new(6510, 2)),
+                (B256::repeat_byte(3), Decimal::new(2170, 2)),
+            ]
+        );
+        assert_eq!(
+            allocations[0].config.reward_min_size,
+            Some(Decimal::from(5))
+        );
+
+        allocator_config.max_markets = 1;
+        let allocations = allocate(&scanned_markets, &allocator_config, &excluded_market_ids);
+        assert_eq!(allocations.len(), 1);
+        assert_eq!(allocations[0].config.order_size, Decimal::new(10417, 2));
+    }
+}
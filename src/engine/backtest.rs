@@ -0,0 +1,167 @@
+use crate::infra::PricePoint;
+use crate::market_logic::market::Market;
+use crate::market_logic::market_types::{NewPrices, OpenOrder, OpenOrderStatus, OrderRequest};
+use crate::types::channel_types::{OrderUpdate, PlacedOrder};
+use polymarket_client_sdk::types::Decimal;
+
+#[derive(Debug, Default)]
+pub struct BacktestReport {
+    pub points: usize,
+    pub orders_placed: usize,
+    pub fills: usize,
+    pub volume: Decimal,
+    pub exposure: Decimal,
+    pub pnl: Decimal,
+}
+
+struct Fill {
+    order_id: String,
+    price: Decimal,
+    amount: Decimal,
+    is_bid: bool,
+}
+
+// Replays a price series against the quoting logic. A resting order is filled
+// in full once the price trades through it; time based features (blackouts,
+// GTD, end of market) still run against the wall clock.
+pub fn simulate(market: &mut Market, price_points: &[PricePoint]) -> BacktestReport {
+    let mut report = BacktestReport::default();
+    let mut cash = Decimal::from(0);
+    let mut yes_shares = Decimal::from(0);
+    let mut no_shares = Decimal::from(0);
+
+    for price_point in price_points {
+        let price = price_point.price;
+        report.points += 1;
+
+        for fill in crossed_orders(market, price) {
+            cash -= fill.price * fill.amount;
+            if fill.is_bid {
+                yes_shares += fill.amount;
+            } else {
+                no_shares += fill.amount;
+            }
+            report.fills += 1;
+            report.volume += fill.amount;
+            market.order_update(OrderUpdate {
+                order_id: fill.order_id,
+                amount: fill.amount,
+            });
+        }
+
+        let midpoint = (price / market.tick_size).round() * market.tick_size;
+        let new_prices = NewPrices {
+            best_bid: midpoint - market.tick_size,
+            best_ask: midpoint + market.tick_size,
+        };
+        let Some(order_requests) = market.price_update(new_prices) else {
+            continue;
+        };
+        for order_request in order_requests {
+            match order_request {
+                OrderRequest::PlaceOrder(order) => {
+                    report.orders_placed += 1;
+                    market.order_placed_update(PlacedOrder {
+                        order_id: format!("backtest-{}", report.orders_placed),
+                        price: order.price,
+                        token_id: order.token_id,
+                    });
+                }
+                OrderRequest::CancelOrder(order_id) => market.cancelled_order_update(order_id),
+            }
+        }
+    }
+
+    let last_price = price_points
+        .last()
+        .map_or(Decimal::from(0), |price_point| price_point.price);
+    report.exposure = market.exposure;
+    report.pnl = cash + yes_shares * last_price + no_shares * (Decimal::from(1) - last_price);
+    report
+}
+
+fn crossed_orders(market: &Market, price: Decimal) -> Vec<Fill> {
+    let placed_fill = |open_order: &OpenOrder, is_bid: bool| match &open_order.status {
+        OpenOrderStatus::Placed(order_id) => Some(Fill {
+            order_id: order_id.clone(),
+            price: open_order.price,
+            amount: open_order.size - open_order.matched,
+            is_bid,
+        }),
+        OpenOrderStatus::Pending => None,
+    };
+    let bid_fills = market
+        .bid_orders
+        .iter()
+        .flatten()
+        .filter(|open_order| price < open_order.price)
+        .filter_map(|open_order| placed_fill(open_order, true));
+    let ask_fills = market
+        .ask_orders
+        .iter()
+        .flatten()
+        .filter(|open_order| price > Decimal::from(1) - open_order.price)
+        .filter_map(|open_order| placed_fill(open_order, false));
+    bid_fills.chain(ask_fills).collect()
+}
+
+pub fn print_report(slug: &str, report: &BacktestReport) {
+    println!(
+        "{}: {} points, {} orders placed, {} fills, volume {}, final exposure {}, pnl {:.2}",
+        slug,
+        report.points,
+        report.orders_placed,
+        report.fills,
+        report.volume,
+        report.exposure,
+        report.pnl
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::config::MarketConfig;
+    use crate::types::{MarketInfo, TokenIds};
+    use alloy::primitives::{B256, U256};
+
+    #[test]
+    fn fills_resting_bid_when_price_trades_through() {
+        let config = MarketConfig::new(
+            "backtest-slug".to_string(),
+            Decimal::from(10),
+            Decimal::new(2, 2),
+            Decimal::from(100),
+        );
+        let market_info = MarketInfo {
+            slug: "backtest-market".to_string(),
+            event_slug: None,
+            token_ids: TokenIds {
+                buy_token: U256::from(1),
+                sell_token: U256::from(2),
+            },
+            condition_id: B256::ZERO,
+            event_start: None,
+            end_date: None,
+            closed: false,
+            accepting_orders: true,
+            neg_risk: false,
+            tick_size: None,
+            min_order_size: None,
+        };
+        let (mut market, _, _) = Market::new(config, market_info).unwrap();
+        let price_point = |price| PricePoint {
+            price: Decimal::new(price, 2),
+        };
+
+        let report = simulate(&mut market, &[price_point(50), price_point(50)]);
+        assert_eq!(report.fills, 0);
+        assert!(report.orders_placed > 0);
+
+        let report = simulate(&mut market, &[price_point(40)]);
+        assert_eq!(report.fills, 1);
+        assert_eq!(report.volume, Decimal::from(10));
+        assert_eq!(market.exposure, Decimal::from(10));
+        assert!(report.pnl < Decimal::from(0));
+    }
+}
@@ -1,76 +1,434 @@
-use crate::infra::{ConfigParams, SigningUtils};
+use crate::engine::allocator::run_allocator;
+use crate::engine::reloader::run_config_reloader;
+use crate::infra::config::{AllocatorConfig, GroupConfig, ReloadConfig};
+use crate::infra::{ClobRestClient, ConfigParams, GammaClient, SigningUtils};
 use crate::market_logic::Market;
 
-use crate::market_logic::market_types::{NewPrices, Order, OrderRequest};
-use crate::types::channel_types::{ChannelData, ChannelMessage, UserData};
+use crate::market_logic::market_types::{Order, OrderRequest};
+use crate::types::channel_types::{
+    Allocation, ChannelData, ChannelMessage, ConfigReload, LifecycleEvent, ManagerMessage,
+    MarketData, RejectedOrder, UserData,
+};
 use crate::websockets::{connect_to_market_ws, connect_to_user_ws};
-use ahash::AHashMap;
-use alloy::primitives::B256;
+use ahash::{AHashMap, AHashSet};
+use alloy::primitives::{B256, U256};
 use crossfire::{MAsyncTx, Rx, mpsc};
 use futures::future::join_all;
-use polymarket_client_sdk::clob::types::Side;
 use polymarket_client_sdk::clob::types::request::CancelMarketOrderRequest;
+use polymarket_client_sdk::clob::types::{OrderType, Side};
+use polymarket_client_sdk::types::Decimal;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+const POST_ONLY_REJECTION: &str = "crosses book";
+const LIFECYCLE_POLL_INTERVAL: Duration = Duration::from_secs(60);
 
 pub struct BotManager {
     markets: AHashMap<B256, Market>,
+    market_tasks: AHashMap<B256, Vec<JoinHandle<()>>>,
+    static_market_ids: AHashSet<B256>,
+    allocator_config: Option<AllocatorConfig>,
+    reload_config: ReloadConfig,
+    config_path: String,
+    group_configs: Vec<GroupConfig>,
     signing_utils: SigningUtils,
+    gamma_client: GammaClient,
+    clob_client: ClobRestClient,
 }
 
 impl BotManager {
-    pub async fn new(config_params: ConfigParams, signing_utils: SigningUtils) -> Self {
-        let markets = Self::get_markets(config_params).await;
-        Self {
-            markets,
+    pub async fn new(
+        config_params: ConfigParams,
+        signing_utils: SigningUtils,
+        gamma_client: GammaClient,
+        clob_client: ClobRestClient,
+    ) -> Self {
+        let allocator_config = config_params.market_configs.allocator.clone();
+        let reload_config = config_params.market_configs.reload.clone();
+        let config_path = config_params.config_path.clone();
+        let group_configs = config_params.market_configs.groups.clone();
+        let markets = Self::get_markets(config_params, &gamma_client).await;
+        let mut bot_manager = Self {
+            markets: AHashMap::new(),
+            market_tasks: AHashMap::new(),
+            static_market_ids: markets.keys().copied().collect(),
+            allocator_config,
+            reload_config,
+            config_path,
+            group_configs,
             signing_utils,
+            gamma_client,
+            clob_client,
+        };
+        for (market_id, market) in markets {
+            bot_manager.insert_market(market_id, market);
         }
+        bot_manager
     }
     pub fn run(mut self) {
         let (rx, tx) = self.start_websockets();
-        while let Ok(message) = rx.recv() {
+        let market_ids: Vec<B256> = self.markets.keys().copied().collect();
+        self.start_ticker(market_ids.clone(), tx.clone());
+        for market_id in market_ids {
+            let poller = self.start_lifecycle_poller(market_id, tx.clone());
+            self.market_tasks.insert(market_id, vec![poller]);
+        }
+        self.start_allocator(tx.clone());
+        self.start_config_reloader(tx.clone());
+        while let Ok(manager_message) = rx.recv() {
+            let message = match manager_message {
+                ManagerMessage::Market(message) => message,
+                ManagerMessage::Reload(config_reload) => {
+                    self.reload_config(config_reload, &tx);
+                    continue;
+                }
+            };
             if let Some(market) = self.markets.get_mut(&message.market_id) {
                 match message.channel_data {
                     ChannelData::OrderActionError => {
-                        self.markets.remove(&message.market_id);
+                        self.remove_market(message.market_id);
                     }
                     ChannelData::MarketData(market_data) => {
-                        let new_prices = NewPrices {
-                            best_bid: market_data.best_bid,
-                            best_ask: market_data.best_ask,
+                        let orders = match market_data {
+                            MarketData::Book(snapshot) => market.book_snapshot(snapshot),
+                            MarketData::PriceChange(change) => market.book_change(change),
                         };
-                        let orders = market.price_update(new_prices);
+                        if market.order_book.needs_snapshot() {
+                            let token_id = market.token_ids.buy_token;
+                            self.request_book_snapshot(token_id, message.market_id, tx.clone());
+                        }
                         if let Some(order_requests) = orders {
-                            for order_request in order_requests {
-                                let order_request_tx = tx.clone();
-                                match order_request {
-                                    OrderRequest::PlaceOrder(order) => {
-                                        self.place_order(order, message.market_id, order_request_tx)
-                                    }
-                                    OrderRequest::CancelOrder(order_id) => self.cancel_order(
-                                        order_id,
-                                        message.market_id,
-                                        order_request_tx,
-                                    ),
-                                }
-                            }
+                            self.handle_order_requests(order_requests, message.market_id, &tx);
+                        }
+                    }
+                    ChannelData::SnapshotFailed => {
+                        market.order_book.snapshot_failed();
+                    }
+                    ChannelData::OrderRejected(rejected_order) => {
+                        market.order_rejected(rejected_order);
+                    }
+                    ChannelData::Tick => {
+                        let orders = market.tick();
+                        if market.order_book.needs_snapshot() {
+                            let token_id = market.token_ids.buy_token;
+                            self.request_book_snapshot(token_id, message.market_id, tx.clone());
+                        }
+                        if let Some(order_requests) = orders {
+                            self.handle_order_requests(order_requests, message.market_id, &tx);
                         }
                     }
                     ChannelData::UserData(user_data) => match user_data {
                         UserData::Placed(placed_order) => {
-                            market.order_placed_update(placed_order);
+                            if let Some(order_id) = market.order_placed_update(placed_order) {
+                                self.cancel_order(order_id, message.market_id, tx.clone());
+                            }
                         }
                         UserData::Update(order_update) => {
                             market.order_update(order_update);
+                            self.update_group_exposure(message.market_id);
                         }
                         UserData::Cancelled(order_id) => {
                             market.cancelled_order_update(order_id);
                         }
                     },
+                    ChannelData::Lifecycle(lifecycle_event) => {
+                        if let Some(order_requests) = market.lifecycle_update(lifecycle_event) {
+                            self.handle_order_requests(order_requests, message.market_id, &tx);
+                        }
+                    }
+                    ChannelData::Allocation(_)
+                        if self.static_market_ids.contains(&message.market_id) => {}
+                    ChannelData::Allocation(allocation) => match allocation {
+                        Allocation::Add(allocated_market) => {
+                            match market.reconfigure(*allocated_market) {
+                                Ok(order_requests) => {
+                                    println!("Re-added market: {}", market.slug);
+                                    if let Some(order_requests) = order_requests {
+                                        self.handle_order_requests(
+                                            order_requests,
+                                            message.market_id,
+                                            &tx,
+                                        );
+                                    }
+                                }
+                                Err(e) => eprintln!(
+                                    "Keeping current config for market: {} error: {e}",
+                                    message.market_id
+                                ),
+                            }
+                        }
+                        Allocation::Resize {
+                            order_size,
+                            max_exposure,
+                        } => market.resize(order_size, max_exposure),
+                        Allocation::Retire => {
+                            println!("Retiring market: {}", market.slug);
+                            if let Some(order_requests) = market.retire(false) {
+                                self.handle_order_requests(order_requests, message.market_id, &tx);
+                            }
+                        }
+                    },
+                }
+                if self
+                    .markets
+                    .get(&message.market_id)
+                    .is_some_and(Market::is_drained)
+                {
+                    self.remove_market(message.market_id);
                 }
+            } else if let ChannelData::Allocation(Allocation::Add(market)) = message.channel_data {
+                self.add_market(*market, message.market_id, &tx);
+            }
+        }
+    }
+
+    fn add_market(&mut self, market: Market, market_id: B256, tx: &MAsyncTx<ManagerMessage>) {
+        println!("Added market for slug: {}", market.slug);
+        let market_data_sender = tx.clone();
+        let asset_ids = vec![market.token_ids.buy_token];
+        let tasks = vec![
+            tokio::spawn(async move { connect_to_market_ws(market_data_sender, asset_ids).await }),
+            self.start_ticker(vec![market_id], tx.clone()),
+            self.start_lifecycle_poller(market_id, tx.clone()),
+        ];
+        self.market_tasks.insert(market_id, tasks);
+        self.insert_market(market_id, market);
+    }
+
+    fn insert_market(&mut self, market_id: B256, mut market: Market) {
+        market.group_config = self.group_config(&market);
+        self.markets.insert(market_id, market);
+        self.update_group_exposure(market_id);
+    }
+
+    fn group_config(&self, market: &Market) -> Option<GroupConfig> {
+        self.group_configs
+            .iter()
+            .find(|group_config| market.group.as_ref() == Some(&group_config.name))
+            .cloned()
+    }
+
+    fn reload_config(&mut self, config_reload: ConfigReload, tx: &MAsyncTx<ManagerMessage>) {
+        let reloaded_market_ids: AHashSet<B256> = config_reload
+            .markets
+            .iter()
+            .map(|(market_id, _)| *market_id)
+            .collect();
+        let removed_market_ids: Vec<B256> = self
+            .static_market_ids
+            .difference(&reloaded_market_ids)
+            .copied()
+            .collect();
+        self.group_configs = config_reload.groups;
+
+        for market_id in removed_market_ids {
+            let Some(market) = self.markets.get_mut(&market_id) else {
+                continue;
+            };
+            println!("Market removed from config, draining: {}", market.slug);
+            if let Some(order_requests) = market.retire(config_reload.flatten_removed) {
+                self.handle_order_requests(order_requests, market_id, tx);
+            }
+            if self.markets.get(&market_id).is_some_and(Market::is_drained) {
+                self.remove_market(market_id);
             }
         }
+        for (market_id, market) in config_reload.markets {
+            match self.markets.get_mut(&market_id) {
+                Some(running_market) => match running_market.reconfigure(market) {
+                    Ok(order_requests) => {
+                        println!("Reconfigured market: {}", running_market.slug);
+                        if let Some(order_requests) = order_requests {
+                            self.handle_order_requests(order_requests, market_id, tx);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Keeping current config for market: {market_id} error: {e}")
+                    }
+                },
+                None => self.add_market(market, market_id, tx),
+            }
+        }
+        self.static_market_ids = reloaded_market_ids;
+
+        let market_ids: Vec<B256> = self.markets.keys().copied().collect();
+        for market_id in market_ids {
+            let group_config = self.group_config(&self.markets[&market_id]);
+            if let Some(market) = self.markets.get_mut(&market_id) {
+                market.group_config = group_config;
+            }
+        }
+        let groups: AHashSet<String> = self
+            .markets
+            .values()
+            .filter_map(|market| market.group.clone())
+            .collect();
+        for group in groups {
+            self.set_group_exposure(&group);
+        }
+    }
+
+    fn remove_market(&mut self, market_id: B256) {
+        if let Some(market) = self.markets.remove(&market_id) {
+            println!("Removed market: {}", market.slug);
+            if let Some(group) = market.group {
+                self.set_group_exposure(&group);
+            }
+        }
+        for task in self.market_tasks.remove(&market_id).unwrap_or_default() {
+            task.abort();
+        }
     }
 
-    pub fn start_websockets(&self) -> (Rx<ChannelMessage>, MAsyncTx<ChannelMessage>) {
+    fn update_group_exposure(&mut self, market_id: B256) {
+        if let Some(group) = self
+            .markets
+            .get(&market_id)
+            .and_then(|market| market.group.clone())
+        {
+            self.set_group_exposure(&group);
+        }
+    }
+
+    fn set_group_exposure(&mut self, group: &str) {
+        let group_exposure: Decimal = self
+            .markets
+            .values()
+            .filter(|market| market.group.as_deref() == Some(group))
+            .map(|market| market.exposure)
+            .sum();
+        for market in self
+            .markets
+            .values_mut()
+            .filter(|market| market.group.as_deref() == Some(group))
+        {
+            market.group_exposure = group_exposure;
+        }
+    }
+
+    fn start_lifecycle_poller(
+        &self,
+        market_id: B256,
+        tx: MAsyncTx<ManagerMessage>,
+    ) -> JoinHandle<()> {
+        let gamma_client = self.gamma_client.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(LIFECYCLE_POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                let markets = match gamma_client.markets_by_condition_id(market_id).await {
+                    Ok(markets) => markets,
+                    Err(e) => {
+                        eprintln!("Error polling market status for: {market_id} error: {e}");
+                        continue;
+                    }
+                };
+                let Some(market) = markets
+                    .iter()
+                    .find(|market| market.condition_id == market_id)
+                else {
+                    continue;
+                };
+                let mut lifecycle_events = vec![if market.closed {
+                    LifecycleEvent::Closed
+                } else if market.accepting_orders == Some(false) {
+                    LifecycleEvent::TradingPaused
+                } else {
+                    LifecycleEvent::TradingResumed
+                }];
+                if let Some(tick_size) = market.order_price_min_tick_size {
+                    lifecycle_events.push(LifecycleEvent::TickSizeChanged(tick_size));
+                }
+                for lifecycle_event in lifecycle_events {
+                    let channel_message = ChannelMessage {
+                        market_id,
+                        channel_data: ChannelData::Lifecycle(lifecycle_event),
+                    };
+                    if let Err(e) = tx.send(channel_message.into()).await {
+                        println!("Error sending market status through data channel. Error: {e}");
+                        return;
+                    }
+                }
+            }
+        })
+    }
+
+    fn start_allocator(&self, tx: MAsyncTx<ManagerMessage>) {
+        let Some(allocator_config) = self.allocator_config.clone() else {
+            return;
+        };
+        let static_market_ids = self.static_market_ids.clone();
+        let gamma_client = self.gamma_client.clone();
+        let clob_client = self.clob_client.clone();
+        tokio::spawn(async move {
+            run_allocator(
+                gamma_client,
+                clob_client,
+                allocator_config,
+                static_market_ids,
+                tx,
+            )
+            .await
+        });
+    }
+
+    fn start_config_reloader(&self, tx: MAsyncTx<ManagerMessage>) {
+        if !self.reload_config.enabled {
+            return;
+        }
+        let config_path = self.config_path.clone();
+        let poll_interval_secs = self.reload_config.poll_interval_secs;
+        let gamma_client = self.gamma_client.clone();
+        tokio::spawn(async move {
+            run_config_reloader(config_path, poll_interval_secs, gamma_client, tx).await
+        });
+    }
+
+    fn handle_order_requests(
+        &self,
+        order_requests: Vec<OrderRequest>,
+        market_id: B256,
+        tx: &MAsyncTx<ManagerMessage>,
+    ) {
+        for order_request in order_requests {
+            let order_request_tx = tx.clone();
+            match order_request {
+                OrderRequest::PlaceOrder(order) => {
+                    self.place_order(order, market_id, order_request_tx)
+                }
+                OrderRequest::CancelOrder(order_id) => {
+                    self.cancel_order(order_id, market_id, order_request_tx)
+                }
+            }
+        }
+    }
+
+    pub fn start_ticker(
+        &self,
+        market_ids: Vec<B256>,
+        tx: MAsyncTx<ManagerMessage>,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TICK_INTERVAL);
+            loop {
+                interval.tick().await;
+                for market_id in &market_ids {
+                    let channel_message = ChannelMessage {
+                        market_id: *market_id,
+                        channel_data: ChannelData::Tick,
+                    };
+                    if let Err(e) = tx.send(channel_message.into()).await {
+                        println!("Error sending tick through data channel. Error: {e}");
+                        return;
+                    }
+                }
+            }
+        })
+    }
+
+    pub fn start_websockets(&self) -> (Rx<ManagerMessage>, MAsyncTx<ManagerMessage>) {
         let mut asset_ids = Vec::new();
 
         for (_, market) in &self.markets {
@@ -91,26 +449,67 @@ impl BotManager {
         });
         (rx.into_blocking(), tx)
     }
-    pub async fn get_markets(config_params: ConfigParams) -> AHashMap<B256, Market> {
+    pub async fn get_markets(
+        config_params: ConfigParams,
+        gamma_client: &GammaClient,
+    ) -> AHashMap<B256, Market> {
         let mut futures = Vec::new();
         let mut markets = AHashMap::new();
 
         for market_config in config_params.market_configs.markets {
-            futures.push(Market::new(market_config));
+            futures.push(Market::from_config(market_config, gamma_client));
         }
         let assigned_market_results = join_all(futures).await;
         for assigned_market_result in assigned_market_results {
             match assigned_market_result {
-                Ok((market, market_id, market_slug)) => {
-                    println!("market created for slug: {}", market_slug);
-                    markets.insert(market_id, market);
+                Ok(assigned_markets) => {
+                    for (market, market_id, market_slug) in assigned_markets {
+                        if market.neg_risk {
+                            println!("neg risk market created for slug: {}", market_slug);
+                        } else {
+                            println!("market created for slug: {}", market_slug);
+                        }
+                        markets.insert(market_id, market);
+                    }
                 }
                 Err(e) => eprintln!("Market creation failed: {e}"),
             }
         }
         markets
     }
-    pub fn cancel_order(&self, order_id: String, market_id: B256, tx: MAsyncTx<ChannelMessage>) {
+    pub fn request_book_snapshot(
+        &self,
+        token_id: U256,
+        market_id: B256,
+        tx: MAsyncTx<ManagerMessage>,
+    ) {
+        let clob_client = self.clob_client.clone();
+
+        tokio::spawn(async move {
+            match clob_client.order_book(token_id).await {
+                Ok(snapshot) => {
+                    let channel_message = ChannelMessage {
+                        market_id,
+                        channel_data: ChannelData::MarketData(MarketData::Book(snapshot)),
+                    };
+                    if let Err(e) = tx.send(channel_message.into()).await {
+                        println!("Error sending book snapshot through data channel. Error: {e}");
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error fetching order book for market: {market_id} error: {e}");
+                    let channel_message = ChannelMessage {
+                        market_id,
+                        channel_data: ChannelData::SnapshotFailed,
+                    };
+                    if let Err(e) = tx.send(channel_message.into()).await {
+                        println!("Error sending snapshot failure through data channel. Error: {e}");
+                    }
+                }
+            }
+        });
+    }
+    pub fn cancel_order(&self, order_id: String, market_id: B256, tx: MAsyncTx<ManagerMessage>) {
         let client = self.signing_utils.client.clone();
 
         tokio::spawn(async move {
@@ -138,7 +537,7 @@ impl BotManager {
                         market_id,
                         channel_data: ChannelData::OrderActionError,
                     };
-                    if let Err(e) = tx.send(channel_message).await {
+                    if let Err(e) = tx.send(channel_message.into()).await {
                         println!(
                             "Error sending order action error through data channel. Error: {e}"
                         );
@@ -147,30 +546,49 @@ impl BotManager {
             }
         });
     }
-    pub fn place_order(&self, order: Order, market_id: B256, tx: MAsyncTx<ChannelMessage>) {
+    pub fn place_order(&self, order: Order, market_id: B256, tx: MAsyncTx<ManagerMessage>) {
         let client = self.signing_utils.client.clone();
         let signer = self.signing_utils.signer.clone();
         let price = order.price;
+        let token_id = order.token_id;
 
         tokio::spawn(async move {
-            let order = client
+            let order_builder = client
                 .limit_order()
                 .token_id(order.token_id)
                 .size(order.size)
                 .price(price)
                 .side(Side::Buy)
-                .build()
-                .await
-                .unwrap();
-            let signed_order = client.sign(&signer, order).await.unwrap();
-            let future = client.post_order(signed_order).await;
-            match future {
+                .post_only(order.post_only)
+                .neg_risk(order.neg_risk);
+            let order_builder = match order.expiration {
+                Some(expiration) => order_builder
+                    .order_type(OrderType::GTD)
+                    .expiration(expiration),
+                None => order_builder,
+            };
+            let future = async {
+                let order = order_builder.build().await?;
+                let signed_order = client.sign(&signer, order).await?;
+                anyhow::Ok(client.post_order(signed_order).await?)
+            };
+            match future.await {
                 Ok(order) => {
                     println!(
                         "Successfully placed order: {} at price: {}",
                         order.order_id, price,
                     );
                 }
+                Err(e) if e.to_string().contains(POST_ONLY_REJECTION) => {
+                    println!("Post-only order at price: {price} rejected for crossing the book");
+                    let channel_message = ChannelMessage {
+                        market_id,
+                        channel_data: ChannelData::OrderRejected(RejectedOrder { price, token_id }),
+                    };
+                    if let Err(e) = tx.send(channel_message.into()).await {
+                        println!("Error sending order rejection through data channel. Error: {e}");
+                    }
+                }
                 Err(e) => {
                     eprintln!(
                         "Failed to place order: {e}\nPanic cancelling all orders for market: {market_id} and shutting down market..."
@@ -192,7 +610,7 @@ impl BotManager {
                         market_id,
                         channel_data: ChannelData::OrderActionError,
                     };
-                    if let Err(e) = tx.send(channel_message).await {
+                    if let Err(e) = tx.send(channel_message.into()).await {
                         println!(
                             "Error sending order action error through data channel. Error: {e}"
                         );
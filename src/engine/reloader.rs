@@ -0,0 +1,76 @@
+use crate::infra::GammaClient;
+use crate::infra::config::MarketConfigs;
+use crate::market_logic::Market;
+use crate::types::channel_types::{ConfigReload, ManagerMessage};
+use anyhow::Result;
+use crossfire::MAsyncTx;
+use futures::future::join_all;
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+pub async fn run_config_reloader(
+    config_path: String,
+    poll_interval_secs: u64,
+    gamma_client: GammaClient,
+    tx: MAsyncTx<ManagerMessage>,
+) {
+    let mut last_modified = modified_time(&config_path).ok();
+    let mut interval = tokio::time::interval(Duration::from_secs(poll_interval_secs.max(1)));
+    loop {
+        interval.tick().await;
+        let modified = match modified_time(&config_path) {
+            Ok(modified) => modified,
+            Err(e) => {
+                eprintln!("Error checking {config_path} for changes: {e}");
+                continue;
+            }
+        };
+        if last_modified == Some(modified) {
+            continue;
+        }
+        last_modified = Some(modified);
+
+        println!("{config_path} changed, reloading markets...");
+        let config_reload = match load_config_reload(&config_path, &gamma_client).await {
+            Ok(config_reload) => config_reload,
+            Err(e) => {
+                eprintln!("Config reload failed, keeping current markets: {e:#}");
+                continue;
+            }
+        };
+        if let Err(e) = tx.send(ManagerMessage::Reload(config_reload)).await {
+            println!("Error sending config reload through data channel. Error: {e}");
+            return;
+        }
+    }
+}
+
+fn modified_time(config_path: &str) -> Result<SystemTime> {
+    Ok(fs::metadata(config_path)?.modified()?)
+}
+
+async fn load_config_reload(config_path: &str, gamma_client: &GammaClient) -> Result<ConfigReload> {
+    let market_configs = MarketConfigs::load_config(config_path)?;
+    let futures = market_configs
+        .markets
+        .into_iter()
+        .map(|market_config| Market::from_config(market_config, gamma_client));
+    let mut markets = Vec::new();
+    for market_result in join_all(futures).await {
+        match market_result {
+            Ok(assigned_markets) => {
+                for (market, market_id, _) in assigned_markets {
+                    markets.push((market_id, market));
+                }
+            }
+            Err(e) => {
+                eprintln!("Skipping market that no longer resolves, treating it as removed: {e:#}")
+            }
+        }
+    }
+    Ok(ConfigReload {
+        markets,
+        groups: market_configs.groups,
+        flatten_removed: market_configs.reload.flatten_removed,
+    })
+}
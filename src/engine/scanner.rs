@@ -0,0 +1,207 @@
+use crate::infra::gamma::GammaMarket;
+use crate::infra::{ClobRestClient, GammaClient, get_reward_markets};
+use crate::market_logic::order_book::OrderBook;
+use alloy::primitives::B256;
+use anyhow::Result;
+use futures::future::join_all;
+use polymarket_client_sdk::types::Decimal;
+
+const MAX_BOOK_REQUESTS: usize = 100;
+
+pub struct ScanParams {
+    pub order_size: Decimal,
+    pub top: usize,
+}
+
+pub struct ScannedMarket {
+    pub condition_id: B256,
+    pub event_slug: String,
+    pub market_slug: String,
+    pub question: String,
+    pub primary_outcome: String,
+    pub daily_rate: Decimal,
+    pub max_spread: Decimal,
+    pub min_size: Decimal,
+    pub midpoint: Decimal,
+    pub spread: Decimal,
+    pub order_size: Decimal,
+    pub competition: Decimal,
+    pub reward_per_dollar: Decimal,
+    pub volatility: Decimal,
+}
+
+impl ScannedMarket {
+    pub fn score(&self) -> Decimal {
+        self.reward_per_dollar * (Decimal::from(1) - self.volatility).max(Decimal::from(0))
+    }
+}
+
+pub async fn scan_markets(
+    gamma_client: &GammaClient,
+    clob_client: &ClobRestClient,
+    params: &ScanParams,
+) -> Result<Vec<ScannedMarket>> {
+    let mut reward_markets = get_reward_markets(gamma_client).await?;
+    reward_markets.sort_by_key(|reward_market| std::cmp::Reverse(reward_market.daily_rate()));
+    reward_markets.truncate(MAX_BOOK_REQUESTS);
+
+    let futures = reward_markets
+        .iter()
+        .map(|reward_market| scan_market(reward_market, clob_client, params.order_size));
+    let mut scanned_markets = Vec::new();
+    for scan_result in join_all(futures).await {
+        match scan_result {
+            Ok(Some(scanned_market)) => scanned_markets.push(scanned_market),
+            Ok(None) => {}
+            Err(e) => eprintln!("Market scan failed: {e}"),
+        }
+    }
+    scanned_markets.sort_by_key(|scanned_market| std::cmp::Reverse(scanned_market.score()));
+    scanned_markets.truncate(params.top);
+    Ok(scanned_markets)
+}
+
+async fn scan_market(
+    reward_market: &GammaMarket,
+    clob_client: &ClobRestClient,
+    order_size: Decimal,
+) -> Result<Option<ScannedMarket>> {
+    let (Some(max_spread_cents), Some(event_slug)) =
+        (reward_market.rewards_max_spread, reward_market.event_slug())
+    else {
+        return Ok(None);
+    };
+    let max_spread = max_spread_cents / Decimal::from(100);
+    let primary_outcome = reward_market.default_primary_outcome()?;
+    let token_ids = reward_market.token_ids(&primary_outcome)?;
+    let mut order_book = OrderBook::new();
+    order_book.apply_snapshot(clob_client.order_book(token_ids.buy_token).await?);
+    let Some(new_prices) = order_book.new_prices() else {
+        return Ok(None);
+    };
+
+    let midpoint = new_prices.midpoint();
+    let min_size = reward_market.rewards_min_size.unwrap_or(Decimal::from(0));
+    let order_size = order_size.max(min_size);
+    let spread = quote_spread(max_spread);
+    let competition = competition_score(&order_book, midpoint, max_spread);
+    let own_score = reward_score(max_spread, spread, order_size) * Decimal::from(2);
+    let capital = order_size * (Decimal::from(1) - spread * Decimal::from(2));
+    let total_score = own_score + competition;
+    if total_score <= Decimal::from(0) || capital <= Decimal::from(0) {
+        return Ok(None);
+    }
+    let reward_share = own_score / total_score;
+    let daily_rate = reward_market.daily_rate();
+
+    Ok(Some(ScannedMarket {
+        condition_id: reward_market.condition_id,
+        event_slug: event_slug.clone(),
+        market_slug: reward_market.slug.clone(),
+        question: reward_market.question.clone(),
+        primary_outcome,
+        daily_rate,
+        max_spread,
+        min_size,
+        midpoint,
+        spread,
+        order_size,
+        competition,
+        reward_per_dollar: daily_rate * reward_share / capital,
+        volatility: reward_market
+            .one_day_price_change
+            .unwrap_or(Decimal::from(0))
+            .abs(),
+    }))
+}
+
+fn quote_spread(max_spread: Decimal) -> Decimal {
+    let tick_size = Decimal::new(1, 2);
+    ((max_spread / Decimal::from(2)) / tick_size)
+        .floor()
+        .max(Decimal::from(1))
+        * tick_size
+}
+
+fn reward_score(max_spread: Decimal, spread: Decimal, size: Decimal) -> Decimal {
+    if spread >= max_spread {
+        return Decimal::from(0);
+    }
+    let distance = (max_spread - spread) / max_spread;
+    distance * distance * size
+}
+
+fn competition_score(order_book: &OrderBook, midpoint: Decimal, max_spread: Decimal) -> Decimal {
+    let bid_score: Decimal = order_book
+        .bids()
+        .map(|(price, size)| reward_score(max_spread, midpoint - price, size))
+        .sum();
+    let ask_score: Decimal = order_book
+        .asks()
+        .map(|(price, size)| reward_score(max_spread, price - midpoint, size))
+        .sum();
+    bid_score + ask_score
+}
+
+pub fn print_table(scanned_markets: &[ScannedMarket]) {
+    println!(
+        "{:>10} {:>10} {:>12} {:>8} {:>8} {:>10}  market",
+        "score", "reward/$", "daily rate", "mid", "vol", "competing"
+    );
+    for scanned_market in scanned_markets {
+        println!(
+            "{:>10.4} {:>10.4} {:>12.2} {:>8.3} {:>8.3} {:>10.0}  {} ({})",
+            scanned_market.score(),
+            scanned_market.reward_per_dollar,
+            scanned_market.daily_rate,
+            scanned_market.midpoint,
+            scanned_market.volatility,
+            scanned_market.competition,
+            scanned_market.question,
+            scanned_market.market_slug,
+        );
+    }
+}
+
+pub fn print_toml(scanned_markets: &[ScannedMarket]) {
+    for scanned_market in scanned_markets {
+        println!("# {}", scanned_market.question);
+        println!("[[markets]]");
+        println!("slug = \"{}\"", scanned_market.event_slug);
+        println!("market = {{ slug = \"{}\" }}", scanned_market.market_slug);
+        println!("primary_outcome = \"{}\"", scanned_market.primary_outcome);
+        println!("order_size = {}", scanned_market.order_size);
+        println!("spread = {}", scanned_market.spread);
+        println!("max_exposure = {}", scanned_market.order_size);
+        println!("reward_max_spread = {}", scanned_market.max_spread);
+        println!("reward_min_size = {}", scanned_market.min_size);
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::channel_types::{BookLevel, BookSnapshot};
+
+    #[test]
+    fn competition_only_counts_liquidity_inside_reward_band() {
+        let level = |price, size| BookLevel {
+            price: Decimal::new(price, 2),
+            size: Decimal::from(size),
+        };
+        let mut order_book = OrderBook::new();
+        order_book.apply_snapshot(BookSnapshot {
+            bids: vec![level(49, 100), level(40, 1000)],
+            asks: vec![level(51, 100), level(53, 100)],
+        });
+        let competition = competition_score(&order_book, Decimal::new(50, 2), Decimal::new(3, 2));
+        let inner_score = Decimal::from(100) * Decimal::from(4) / Decimal::from(9);
+        assert_eq!(
+            competition.round_dp(6),
+            (inner_score * Decimal::from(2)).round_dp(6)
+        );
+        assert_eq!(quote_spread(Decimal::new(35, 3)), Decimal::new(1, 2));
+        assert_eq!(quote_spread(Decimal::new(6, 2)), Decimal::new(3, 2));
+    }
+}
@@ -1,7 +1,10 @@
 pub mod api;
 pub mod client;
 pub mod config;
+pub mod gamma;
+pub mod rest;
 
 pub use api::*;
 pub use client::SigningUtils;
 pub use config::ConfigParams;
+pub use gamma::GammaClient;
@@ -1,37 +1,306 @@
-use crate::types::TokenIds;
-use alloy::primitives::{B256, U256};
-use anyhow::{Context, Result};
+use crate::infra::config::{HttpConfig, MarketIdentifier, MarketSelector};
+use crate::infra::gamma::{DEFAULT_PRIMARY_OUTCOME, GammaClient, GammaMarket, MarketFilter};
+use crate::infra::rest::RestClient;
+use crate::types::MarketInfo;
+use crate::types::channel_types::{BookLevel, BookSnapshot};
+use alloy::primitives::{Address, B256, U256};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use polymarket_client_sdk::types::Decimal;
 use serde::Deserialize;
 
+fn matches(market: &GammaMarket, selector: &MarketSelector) -> bool {
+    match selector {
+        MarketSelector::Only | MarketSelector::All => true,
+        MarketSelector::Slug(slug) => market.slug == *slug,
+        MarketSelector::Question(question) => market.question.eq_ignore_ascii_case(question),
+        MarketSelector::Outcome(outcome) => market
+            .group_item_title
+            .as_ref()
+            .is_some_and(|title| title.eq_ignore_ascii_case(outcome)),
+    }
+}
+
+fn market_info(
+    market: &GammaMarket,
+    event: Option<(&str, Option<DateTime<Utc>>)>,
+    primary_outcome: Option<&str>,
+) -> Result<MarketInfo> {
+    let (event_slug, event_start) = match event {
+        Some((event_slug, event_start)) => (Some(event_slug), event_start),
+        None => (market.event_slug().map(String::as_str), None),
+    };
+    let token_ids = market.token_ids(primary_outcome.unwrap_or(DEFAULT_PRIMARY_OUTCOME))?;
+
+    Ok(MarketInfo {
+        slug: market.slug.clone(),
+        event_slug: event_slug.map(str::to_string),
+        token_ids,
+        condition_id: market.condition_id,
+        event_start: market.event_start()?.or(event_start),
+        end_date: market.end_date,
+        closed: market.closed,
+        accepting_orders: market.accepting_orders.unwrap_or(true),
+        neg_risk: market.neg_risk,
+        tick_size: market.order_price_min_tick_size,
+        min_order_size: market.order_min_size,
+    })
+}
+
+pub async fn get_market_infos(
+    gamma_client: &GammaClient,
+    identifier: &MarketIdentifier<'_>,
+    primary_outcome: Option<&str>,
+) -> Result<Vec<MarketInfo>> {
+    match identifier {
+        MarketIdentifier::EventSlug(slug, selector) => {
+            get_event_market_infos(gamma_client, slug, selector, primary_outcome).await
+        }
+        MarketIdentifier::MarketSlug(market_slug) => {
+            let market = gamma_client.market_by_slug(market_slug).await?;
+            Ok(vec![market_info(&market, None, primary_outcome)?])
+        }
+        MarketIdentifier::ConditionId(condition_id) => {
+            let markets = gamma_client.markets_by_condition_id(*condition_id).await?;
+            let market = markets
+                .iter()
+                .find(|market| market.condition_id == *condition_id)
+                .with_context(|| format!("No market found for condition id {condition_id}"))?;
+            Ok(vec![market_info(market, None, primary_outcome)?])
+        }
+        MarketIdentifier::TokenIds(outcome_token_ids) => {
+            let markets = gamma_client
+                .markets_by_token_id(outcome_token_ids.yes)
+                .await?;
+            let market = markets
+                .first()
+                .with_context(|| format!("No market found for {identifier}"))?;
+            let market_info = market_info(market, None, primary_outcome)?;
+            if market_info.token_ids.buy_token != outcome_token_ids.yes
+                || market_info.token_ids.sell_token != outcome_token_ids.no
+            {
+                bail!(
+                    "Configured {identifier} do not match the tokens of market {}",
+                    market_info.slug
+                );
+            }
+            Ok(vec![market_info])
+        }
+    }
+}
+
+async fn get_event_market_infos(
+    gamma_client: &GammaClient,
+    slug: &str,
+    selector: &MarketSelector,
+    primary_outcome: Option<&str>,
+) -> Result<Vec<MarketInfo>> {
+    let event = gamma_client.event_by_slug(slug).await?;
+    select_markets(slug, &event.markets, selector)?
+        .into_iter()
+        .map(|market| market_info(market, Some((slug, event.start_date)), primary_outcome))
+        .collect()
+}
+
+fn select_markets<'a>(
+    slug: &str,
+    markets: &'a [GammaMarket],
+    selector: &MarketSelector,
+) -> Result<Vec<&'a GammaMarket>> {
+    let markets: Vec<&GammaMarket> = markets
+        .iter()
+        .filter(|market| matches(market, selector))
+        .collect();
+
+    if markets.is_empty() {
+        bail!("No market in event {slug} matches {selector:?}");
+    }
+    if markets.len() > 1 && !matches!(selector, MarketSelector::All) {
+        let market_slugs: Vec<&str> = markets.iter().map(|market| market.slug.as_str()).collect();
+        bail!(
+            "Event {slug} has {} matching markets, select one with `market` or use `market = \"all\"`: {}",
+            markets.len(),
+            market_slugs.join(", ")
+        );
+    }
+    Ok(markets)
+}
+
+pub async fn get_reward_markets(gamma_client: &GammaClient) -> Result<Vec<GammaMarket>> {
+    let market_filter = MarketFilter {
+        active: Some(true),
+        closed: Some(false),
+    };
+    let markets = gamma_client.list_markets(&market_filter).await?;
+    Ok(markets
+        .into_iter()
+        .filter(|market| market.daily_rate() > Decimal::from(0))
+        .collect())
+}
+
 #[derive(Deserialize)]
-struct MarketResponse {
-    markets: Vec<IndividualMarket>,
+struct BookResponse {
+    bids: Vec<BookResponseLevel>,
+    asks: Vec<BookResponseLevel>,
 }
 
 #[derive(Deserialize)]
-struct IndividualMarket {
-    #[serde(rename = "clobTokenIds")]
-    clob_token_ids: String,
-    #[serde(rename = "conditionId")]
-    condition_id: B256,
-}
-pub async fn get_token_id(slug: &String) -> Result<(TokenIds, B256)> {
-    let url = format!("https://gamma-api.polymarket.com/events/slug/{}", slug);
-    let response = reqwest::get(url)
-        .await
-        .context("failed to fetch clob token ids")?
-        .text()
-        .await?;
-    let market_response: MarketResponse = serde_json::from_str(&response)
-        .with_context(|| format!("Invalid market_logic response for: {}", slug))?;
-    let market: &IndividualMarket = &market_response.markets[0];
-    let token_vec: Vec<U256> =
-        serde_json::from_str(&market.clob_token_ids).context("failed to parse token vec json")?;
-    let market_identifier: B256 = market.condition_id;
-
-    let token_ids = TokenIds {
-        buy_token: token_vec[0],
-        sell_token: token_vec[1],
-    };
-    Ok((token_ids, market_identifier))
+struct BookResponseLevel {
+    price: Decimal,
+    size: Decimal,
+}
+
+#[derive(Clone)]
+pub struct ClobRestClient {
+    rest_client: RestClient,
+}
+
+impl ClobRestClient {
+    pub fn new(http_config: &HttpConfig) -> Result<Self> {
+        Ok(Self {
+            rest_client: http_config.rest_client("CLOB", "https://clob.polymarket.com")?,
+        })
+    }
+
+    pub async fn order_book(&self, token_id: U256) -> Result<BookSnapshot> {
+        let book_response: BookResponse = self
+            .rest_client
+            .get("book", &[("token_id", token_id.to_string())])
+            .await?;
+        let book_levels = |levels: Vec<BookResponseLevel>| {
+            levels
+                .into_iter()
+                .map(|level| BookLevel {
+                    price: level.price,
+                    size: level.size,
+                })
+                .collect()
+        };
+        Ok(BookSnapshot {
+            bids: book_levels(book_response.bids),
+            asks: book_levels(book_response.asks),
+        })
+    }
+
+    pub async fn price_history(
+        &self,
+        token_id: U256,
+        interval: &str,
+        fidelity: u32,
+    ) -> Result<Vec<PricePoint>> {
+        let price_history: PriceHistoryResponse = self
+            .rest_client
+            .get(
+                "prices-history",
+                &[
+                    ("market", token_id.to_string()),
+                    ("interval", interval.to_string()),
+                    ("fidelity", fidelity.to_string()),
+                ],
+            )
+            .await?;
+        Ok(price_history.history)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Position {
+    pub condition_id: B256,
+    pub slug: String,
+    pub outcome: String,
+    pub size: Decimal,
+    pub avg_price: Decimal,
+    pub cur_price: Decimal,
+    pub cash_pnl: Decimal,
+}
+
+pub struct DataApiClient {
+    rest_client: RestClient,
+}
+
+impl DataApiClient {
+    pub fn new(http_config: &HttpConfig) -> Result<Self> {
+        Ok(Self {
+            rest_client: http_config.rest_client("Data API", "https://data-api.polymarket.com")?,
+        })
+    }
+
+    pub async fn positions(&self, user: Address) -> Result<Vec<Position>> {
+        self.rest_client
+            .get("positions", &[("user", user.to_string())])
+            .await
+    }
+}
+
+#[derive(Deserialize)]
+struct PriceHistoryResponse {
+    history: Vec<PricePoint>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct PricePoint {
+    #[serde(rename = "p")]
+    pub price: Decimal,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gamma_market(slug: &str, group_item_title: &str) -> GammaMarket {
+        serde_json::from_str(&format!(
+            r#"{{"slug": "{slug}", "question": "Will {group_item_title} win?", "groupItemTitle": "{group_item_title}", "conditionId": "{}"}}"#,
+            B256::ZERO
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn selects_event_markets_by_slug_question_or_outcome() {
+        let markets = vec![
+            gamma_market("event-alice", "Alice"),
+            gamma_market("event-bob", "Bob"),
+        ];
+        let selected_slugs = |selector| {
+            select_markets("event", &markets, &selector).map(|markets| {
+                markets
+                    .iter()
+                    .map(|market| market.slug.clone())
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        assert_eq!(
+            selected_slugs(MarketSelector::Slug("event-bob".to_string())).unwrap(),
+            ["event-bob"]
+        );
+        assert_eq!(
+            selected_slugs(MarketSelector::Question("will alice win?".to_string())).unwrap(),
+            ["event-alice"]
+        );
+        assert_eq!(
+            selected_slugs(MarketSelector::Outcome("BOB".to_string())).unwrap(),
+            ["event-bob"]
+        );
+        assert_eq!(
+            selected_slugs(MarketSelector::All).unwrap(),
+            ["event-alice", "event-bob"]
+        );
+        assert!(selected_slugs(MarketSelector::Outcome("Carol".to_string())).is_err());
+
+        let error = selected_slugs(MarketSelector::Only)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("2 matching markets"));
+        assert!(error.contains("event-alice, event-bob"));
+
+        let single_market = vec![gamma_market("event-alice", "Alice")];
+        assert_eq!(
+            select_markets("event", &single_market, &MarketSelector::Only)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
 }
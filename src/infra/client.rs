@@ -2,9 +2,10 @@ use alloy::primitives::Address;
 use alloy::signers::Signer;
 use alloy::signers::local::{LocalSigner, PrivateKeySigner};
 use anyhow::{Context, Result};
+use chrono::{TimeDelta, Utc};
 use polymarket_client_sdk::POLYGON;
-use polymarket_client_sdk::auth::Normal;
 use polymarket_client_sdk::auth::state::Authenticated;
+use polymarket_client_sdk::auth::{Credentials, Normal};
 use polymarket_client_sdk::clob::types::SignatureType;
 use polymarket_client_sdk::clob::{Client, Config};
 use std::str::FromStr as _;
@@ -18,9 +19,7 @@ pub struct SigningUtils {
 
 impl SigningUtils {
     pub async fn new_client(private_key: &str, funder_address: &str) -> Result<Self> {
-        let signer = LocalSigner::from_str(private_key)
-            .context("Invalid private key")?
-            .with_chain_id(Some(POLYGON));
+        let signer = Self::signer(private_key)?;
         let funder: Address = funder_address.parse().context("Invalid funder address")?;
         let client = Client::new("https://clob.polymarket.com", Config::default())?
             .authentication_builder(&signer)
@@ -35,4 +34,24 @@ impl SigningUtils {
             funder_address: funder,
         })
     }
+    pub async fn derive_api_key(private_key: &str) -> Result<Credentials> {
+        let signer = Self::signer(private_key)?;
+        Client::new("https://clob.polymarket.com", Config::default())?
+            .derive_api_key(&signer, None)
+            .await
+            .context("Failed to derive API key")
+    }
+    fn signer(private_key: &str) -> Result<PrivateKeySigner> {
+        Ok(LocalSigner::from_str(private_key)
+            .context("Invalid private key")?
+            .with_chain_id(Some(POLYGON)))
+    }
+    pub async fn clock_skew(&self) -> Result<TimeDelta> {
+        let server_time = self
+            .client
+            .server_time()
+            .await
+            .context("Failed to get server time")?;
+        Ok(TimeDelta::seconds(server_time - Utc::now().timestamp()))
+    }
 }
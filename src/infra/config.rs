@@ -1,43 +1,281 @@
-use anyhow::{Context, Result};
+use crate::infra::rest::RestClient;
+use alloy::primitives::{B256, U256};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, TimeDelta, Utc};
 use dotenv::dotenv;
 use polymarket_client_sdk::types::Decimal;
 use serde::Deserialize;
 use std::env;
+use std::fmt;
 use std::fs;
+use std::time::Duration;
 
 #[derive(Deserialize, Debug)]
 pub struct MarketConfigs {
+    #[serde(default)]
     pub markets: Vec<MarketConfig>,
+    pub allocator: Option<AllocatorConfig>,
+    #[serde(default)]
+    pub gamma: GammaConfig,
+    #[serde(default)]
+    pub http: HttpConfig,
+    #[serde(default)]
+    pub groups: Vec<GroupConfig>,
+    #[serde(default)]
+    pub acknowledge_risk: bool,
+    #[serde(default)]
+    pub reload: ReloadConfig,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct GroupConfig {
+    pub name: String,
+    pub max_exposure: Decimal,
+    pub max_skew: Option<Decimal>,
+    #[serde(default)]
+    pub max_size_skew: Decimal,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct GammaConfig {
+    pub base_url: String,
+    pub page_size: usize,
+}
+
+impl Default for GammaConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "https://gamma-api.polymarket.com".to_string(),
+            page_size: 500,
+        }
+    }
+}
+
+impl GammaConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.page_size == 0 {
+            bail!("`page_size` must be greater than 0");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct HttpConfig {
+    pub timeout_ms: u64,
+    pub max_retries: u32,
+    pub retry_backoff_ms: u64,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            timeout_ms: 10_000,
+            max_retries: 3,
+            retry_backoff_ms: 500,
+        }
+    }
+}
+
+const MAX_RETRIES: u32 = 10;
+
+impl HttpConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.max_retries > MAX_RETRIES {
+            bail!("`max_retries` must be at most {MAX_RETRIES}");
+        }
+        Ok(())
+    }
+
+    pub fn rest_client(&self, name: &'static str, base_url: &str) -> Result<RestClient> {
+        RestClient::new(
+            name,
+            base_url,
+            Duration::from_millis(self.timeout_ms),
+            self.max_retries,
+            Duration::from_millis(self.retry_backoff_ms),
+        )
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ReloadConfig {
+    pub enabled: bool,
+    pub poll_interval_secs: u64,
+    pub flatten_removed: bool,
+}
+
+impl Default for ReloadConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            poll_interval_secs: 5,
+            flatten_removed: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AllocatorConfig {
+    pub total_capital: Decimal,
+    pub max_markets: usize,
+    #[serde(default = "default_rescan_interval_secs")]
+    pub rescan_interval_secs: u64,
+    #[serde(default)]
+    pub min_score: Decimal,
+}
+
+fn default_rescan_interval_secs() -> u64 {
+    3600
+}
+
+#[derive(Deserialize, Debug, Clone)]
 pub struct MarketConfig {
-    pub slug: String,
+    pub slug: Option<String>,
+    pub market_slug: Option<String>,
+    pub condition_id: Option<B256>,
+    pub token_ids: Option<OutcomeTokenIds>,
+    pub primary_outcome: Option<String>,
+    pub group: Option<String>,
+    #[serde(default)]
+    pub market: MarketSelector,
     pub order_size: Decimal,
     pub spread: Decimal,
     pub max_exposure: Decimal,
+    #[serde(default)]
+    pub pricing_mode: PricingMode,
+    #[serde(default)]
+    pub depth: DepthConfig,
+    #[serde(default)]
+    pub levels: Vec<QuoteLevel>,
+    #[serde(default)]
+    pub requote: RequoteConfig,
+    pub reward_max_spread: Option<Decimal>,
+    pub reward_min_size: Option<Decimal>,
+    #[serde(default)]
+    pub skew: SkewConfig,
+    pub volatility: Option<VolatilityConfig>,
+    pub protection: Option<ProtectionConfig>,
+    #[serde(default)]
+    pub blackouts: Vec<BlackoutWindow>,
+    pub stop_before_end_mins: Option<i64>,
+    #[serde(default)]
+    pub flatten_before_end: bool,
+    pub gtd: Option<GtdConfig>,
+    #[serde(default = "default_post_only")]
+    pub post_only: bool,
+}
+
+fn default_post_only() -> bool {
+    true
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct OutcomeTokenIds {
+    pub yes: U256,
+    pub no: U256,
+}
+
+pub enum MarketIdentifier<'a> {
+    EventSlug(&'a String, &'a MarketSelector),
+    MarketSlug(&'a String),
+    ConditionId(B256),
+    TokenIds(&'a OutcomeTokenIds),
+}
+
+impl fmt::Display for MarketIdentifier<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarketIdentifier::EventSlug(slug, _) => write!(f, "event slug {slug}"),
+            MarketIdentifier::MarketSlug(market_slug) => write!(f, "market slug {market_slug}"),
+            MarketIdentifier::ConditionId(condition_id) => write!(f, "condition id {condition_id}"),
+            MarketIdentifier::TokenIds(token_ids) => {
+                write!(f, "token ids {}/{}", token_ids.yes, token_ids.no)
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketSelector {
+    #[default]
+    Only,
+    All,
+    Slug(String),
+    Question(String),
+    Outcome(String),
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct QuoteLevel {
+    pub offset: Decimal,
+    pub size: Decimal,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PricingMode {
+    #[default]
+    BestPrice,
+    Midpoint,
+    Depth,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct DepthConfig {
+    pub size_ahead: Option<Decimal>,
+    pub level: Option<usize>,
+}
+
+impl DepthConfig {
+    pub fn price<I>(&self, levels: I) -> Option<Decimal>
+    where
+        I: Iterator<Item = (Decimal, Decimal)>,
+    {
+        let mut cumulative_size = Decimal::from(0);
+        let mut last_price = None;
+        for (index, (price, size)) in levels.enumerate() {
+            cumulative_size += size;
+            last_price = Some(price);
+            let level_reached = self.level.is_none_or(|level| index + 1 >= level);
+            let size_reached = self
+                .size_ahead
+                .is_none_or(|size_ahead| cumulative_size >= size_ahead);
+            if level_reached && size_reached {
+                return last_price;
+            }
+        }
+        last_price
+    }
 }
 
 #[derive(Debug)]
 pub struct ConfigParams {
+    pub config_path: String,
     pub funder_address: String,
     pub private_key: String,
     pub market_configs: MarketConfigs,
 }
 
 impl ConfigParams {
-    pub fn new() -> Result<Self> {
+    pub fn new(config_path: &str) -> Result<Self> {
         let (funder_address, private_key) =
             Self::load_env_vars().context("Failed to load env vars")?;
-        let market_configs =
-            MarketConfigs::load_config().context("Failed to load market_logic configs")?;
+        let market_configs = MarketConfigs::load_config(config_path)
+            .context("Failed to load market_logic configs")?;
         Ok(Self {
+            config_path: config_path.to_string(),
             funder_address,
             private_key,
             market_configs,
         })
     }
-    fn load_env_vars() -> Result<(String, String)> {
+    pub fn load_env_vars() -> Result<(String, String)> {
         dotenv().context("Failed to read .env")?;
         let private_key = env::var("PRIVATE_KEY").context("Failed to read PRIVATE_KEY_PATH")?;
         let funder_address = env::var("FUNDER_ADDRESS").context("Failed to get funder address")?;
@@ -45,11 +283,317 @@ impl ConfigParams {
     }
 }
 
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RequoteConfig {
+    pub min_move_ticks: u32,
+    pub min_order_age_ms: u64,
+}
+
+impl Default for RequoteConfig {
+    fn default() -> Self {
+        Self {
+            min_move_ticks: 1,
+            min_order_age_ms: 0,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct SkewConfig {
+    pub shape: SkewShape,
+    pub max_skew: Option<Decimal>,
+    pub max_size_skew: Decimal,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SkewShape {
+    #[default]
+    Linear,
+    Quadratic,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct VolatilityConfig {
+    pub window_secs: u64,
+    pub min_spread: Decimal,
+    pub max_spread: Decimal,
+    pub multiplier: Decimal,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ProtectionConfig {
+    pub sweep_levels: Option<usize>,
+    pub sweep_window_ms: u64,
+    pub imbalance_threshold: Option<Decimal>,
+    pub imbalance_levels: usize,
+    pub mid_jump: Option<Decimal>,
+    pub cooldown_secs: u64,
+}
+
+impl Default for ProtectionConfig {
+    fn default() -> Self {
+        Self {
+            sweep_levels: None,
+            sweep_window_ms: 1000,
+            imbalance_threshold: None,
+            imbalance_levels: 3,
+            mid_jump: None,
+            cooldown_secs: 30,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BlackoutWindow {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub before_event_start_mins: Option<i64>,
+    pub after_event_start_mins: Option<i64>,
+    pub spread_multiplier: Option<Decimal>,
+}
+
+impl BlackoutWindow {
+    pub fn contains(&self, now: DateTime<Utc>, event_start: Option<DateTime<Utc>>) -> bool {
+        let relative_start = self
+            .before_event_start_mins
+            .zip(event_start)
+            .map(|(mins, event_start)| event_start - TimeDelta::minutes(mins));
+        let relative_end = self
+            .after_event_start_mins
+            .zip(event_start)
+            .map(|(mins, event_start)| event_start + TimeDelta::minutes(mins));
+        let start = self.start.or(relative_start);
+        let end = self.end.or(relative_end);
+        if start.is_none() && end.is_none() {
+            return false;
+        }
+        start.is_none_or(|start| now >= start) && end.is_none_or(|end| now < end)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct GtdConfig {
+    pub expiration_secs: u64,
+    pub refresh_before_secs: u64,
+}
+
+impl MarketConfig {
+    pub fn new(slug: String, order_size: Decimal, spread: Decimal, max_exposure: Decimal) -> Self {
+        Self {
+            slug: Some(slug),
+            market_slug: None,
+            condition_id: None,
+            token_ids: None,
+            primary_outcome: None,
+            group: None,
+            market: MarketSelector::default(),
+            order_size,
+            spread,
+            max_exposure,
+            pricing_mode: PricingMode::default(),
+            depth: DepthConfig::default(),
+            levels: Vec::new(),
+            requote: RequoteConfig::default(),
+            reward_max_spread: None,
+            reward_min_size: None,
+            skew: SkewConfig::default(),
+            volatility: None,
+            protection: None,
+            blackouts: Vec::new(),
+            stop_before_end_mins: None,
+            flatten_before_end: false,
+            gtd: None,
+            post_only: default_post_only(),
+        }
+    }
+
+    pub fn identifier(&self) -> Result<MarketIdentifier<'_>> {
+        let identifier = match (
+            &self.slug,
+            &self.market_slug,
+            self.condition_id,
+            &self.token_ids,
+        ) {
+            (Some(slug), None, None, None) => MarketIdentifier::EventSlug(slug, &self.market),
+            (None, Some(market_slug), None, None) => MarketIdentifier::MarketSlug(market_slug),
+            (None, None, Some(condition_id), None) => MarketIdentifier::ConditionId(condition_id),
+            (None, None, None, Some(token_ids)) => MarketIdentifier::TokenIds(token_ids),
+            _ => bail!(
+                "Market config needs exactly one of slug, market_slug, condition_id or token_ids"
+            ),
+        };
+        if self.slug.is_none() && self.market != MarketSelector::Only {
+            bail!("`market` can only be used together with an event `slug`");
+        }
+        Ok(identifier)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.identifier()?;
+        if let Some(gtd) = &self.gtd
+            && gtd.refresh_before_secs >= gtd.expiration_secs
+        {
+            bail!("`gtd.refresh_before_secs` must be less than `gtd.expiration_secs`");
+        }
+        Ok(())
+    }
+
+    pub fn price_history_window(&self) -> Duration {
+        let window_secs = self
+            .volatility
+            .as_ref()
+            .map_or(60, |volatility| volatility.window_secs);
+        Duration::from_secs(window_secs)
+    }
+
+    pub fn active_blackout(
+        &self,
+        now: DateTime<Utc>,
+        event_start: Option<DateTime<Utc>>,
+    ) -> Option<&BlackoutWindow> {
+        self.blackouts
+            .iter()
+            .find(|blackout| blackout.contains(now, event_start))
+    }
+
+    pub fn gtd_refresh_after(&self) -> Option<Duration> {
+        self.gtd.as_ref().map(|gtd| {
+            Duration::from_secs(gtd.expiration_secs.saturating_sub(gtd.refresh_before_secs))
+        })
+    }
+
+    pub fn quote_levels(&self) -> Vec<QuoteLevel> {
+        if self.levels.is_empty() {
+            vec![QuoteLevel {
+                offset: Decimal::from(0),
+                size: self.order_size,
+            }]
+        } else {
+            self.levels.clone()
+        }
+    }
+}
+
 impl MarketConfigs {
-    fn load_config() -> Result<Self> {
-        let content = fs::read_to_string("config.toml").context("Failed to read config.toml")?;
-        let content_toml = toml::from_str(&content).context("Failed to parse config.toml")?;
+    pub fn load_config(config_path: &str) -> Result<Self> {
+        let content = fs::read_to_string(config_path)
+            .with_context(|| format!("Failed to read {config_path}"))?;
+        let market_configs: Self =
+            toml::from_str(&content).with_context(|| format!("Failed to parse {config_path}"))?;
+        for (index, market_config) in market_configs.markets.iter().enumerate() {
+            market_config
+                .validate()
+                .with_context(|| format!("Invalid market #{} in {config_path}", index + 1))?;
+        }
+        market_configs
+            .gamma
+            .validate()
+            .with_context(|| format!("Invalid [gamma] section in {config_path}"))?;
+        market_configs
+            .http
+            .validate()
+            .with_context(|| format!("Invalid [http] section in {config_path}"))?;
+
+        Ok(market_configs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn market_config_needs_exactly_one_identifier() {
+        let mut market_config = MarketConfig::new(
+            "event-slug".to_string(),
+            Decimal::from(5),
+            Decimal::new(2, 2),
+            Decimal::from(50),
+        );
+        assert!(matches!(
+            market_config.identifier(),
+            Ok(MarketIdentifier::EventSlug(slug, MarketSelector::Only)) if slug == "event-slug"
+        ));
+
+        market_config.condition_id = Some(B256::ZERO);
+        assert!(market_config.identifier().is_err());
+
+        market_config.slug = None;
+        assert!(matches!(
+            market_config.identifier(),
+            Ok(MarketIdentifier::ConditionId(_))
+        ));
+
+        market_config.condition_id = None;
+        assert!(market_config.identifier().is_err());
+
+        market_config.token_ids = Some(OutcomeTokenIds {
+            yes: U256::from(1),
+            no: U256::from(2),
+        });
+        assert!(matches!(
+            market_config.identifier(),
+            Ok(MarketIdentifier::TokenIds(_))
+        ));
+    }
+
+    #[test]
+    fn market_selector_needs_event_slug() {
+        let mut market_config = MarketConfig::new(
+            "event-slug".to_string(),
+            Decimal::from(5),
+            Decimal::new(2, 2),
+            Decimal::from(50),
+        );
+        market_config.market = MarketSelector::All;
+        assert!(market_config.identifier().is_ok());
+
+        market_config.slug = None;
+        market_config.market_slug = Some("market-slug".to_string());
+        assert!(market_config.identifier().is_err());
+
+        market_config.market = MarketSelector::Only;
+        assert!(matches!(
+            market_config.identifier(),
+            Ok(MarketIdentifier::MarketSlug(market_slug)) if market_slug == "market-slug"
+        ));
+    }
+
+    #[test]
+    fn rejects_gtd_refresh_at_or_after_expiration() {
+        let mut market_config = MarketConfig::new(
+            "event-slug".to_string(),
+            Decimal::from(5),
+            Decimal::new(2, 2),
+            Decimal::from(50),
+        );
+        market_config.gtd = Some(GtdConfig {
+            expiration_secs: 300,
+            refresh_before_secs: 60,
+        });
+        assert!(market_config.validate().is_ok());
+
+        market_config.gtd = Some(GtdConfig {
+            expiration_secs: 300,
+            refresh_before_secs: 300,
+        });
+        assert!(market_config.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_empty_gamma_pages_and_unbounded_retries() {
+        let mut gamma_config = GammaConfig::default();
+        assert!(gamma_config.validate().is_ok());
+        gamma_config.page_size = 0;
+        assert!(gamma_config.validate().is_err());
 
-        Ok(content_toml)
+        let mut http_config = HttpConfig::default();
+        assert!(http_config.validate().is_ok());
+        http_config.max_retries = 32;
+        assert!(http_config.validate().is_err());
     }
 }
@@ -0,0 +1,322 @@
+use crate::infra::config::{GammaConfig, HttpConfig};
+use crate::infra::rest::RestClient;
+use crate::types::TokenIds;
+use alloy::primitives::{B256, U256};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use polymarket_client_sdk::types::Decimal;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+pub const DEFAULT_PRIMARY_OUTCOME: &str = "Yes";
+
+#[derive(Clone)]
+pub struct GammaClient {
+    rest_client: RestClient,
+    page_size: usize,
+}
+
+#[derive(Default)]
+pub struct MarketFilter {
+    pub active: Option<bool>,
+    pub closed: Option<bool>,
+}
+
+impl MarketFilter {
+    fn query(&self) -> Vec<(&'static str, String)> {
+        let mut query = Vec::new();
+        if let Some(active) = self.active {
+            query.push(("active", active.to_string()));
+        }
+        if let Some(closed) = self.closed {
+            query.push(("closed", closed.to_string()));
+        }
+        query
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GammaEvent {
+    pub start_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub markets: Vec<GammaMarket>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GammaMarket {
+    pub slug: String,
+    pub question: String,
+    pub group_item_title: Option<String>,
+    pub condition_id: B256,
+    clob_token_ids: Option<String>,
+    outcomes: Option<String>,
+    game_start_time: Option<String>,
+    pub end_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub closed: bool,
+    pub accepting_orders: Option<bool>,
+    #[serde(default)]
+    pub neg_risk: bool,
+    pub order_price_min_tick_size: Option<Decimal>,
+    pub order_min_size: Option<Decimal>,
+    pub rewards_min_size: Option<Decimal>,
+    pub rewards_max_spread: Option<Decimal>,
+    #[serde(default)]
+    pub clob_rewards: Vec<ClobReward>,
+    pub one_day_price_change: Option<Decimal>,
+    #[serde(default)]
+    pub events: Vec<GammaMarketEvent>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ClobReward {
+    pub rewards_daily_rate: Decimal,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GammaMarketEvent {
+    pub slug: String,
+    pub start_date: Option<DateTime<Utc>>,
+}
+
+impl GammaClient {
+    pub fn new(gamma_config: &GammaConfig, http_config: &HttpConfig) -> Result<Self> {
+        Ok(Self {
+            rest_client: http_config.rest_client("Gamma", &gamma_config.base_url)?,
+            page_size: gamma_config.page_size,
+        })
+    }
+
+    pub async fn event_by_slug(&self, slug: &str) -> Result<GammaEvent> {
+        self.rest_client
+            .get(&format!("events/slug/{}", slug), &[])
+            .await
+    }
+
+    pub async fn market_by_slug(&self, market_slug: &str) -> Result<GammaMarket> {
+        self.rest_client
+            .get(&format!("markets/slug/{}", market_slug), &[])
+            .await
+    }
+
+    pub async fn markets_by_condition_id(&self, condition_id: B256) -> Result<Vec<GammaMarket>> {
+        self.rest_client
+            .get("markets", &[("condition_ids", condition_id.to_string())])
+            .await
+    }
+
+    pub async fn markets_by_token_id(&self, token_id: U256) -> Result<Vec<GammaMarket>> {
+        self.rest_client
+            .get("markets", &[("clob_token_ids", token_id.to_string())])
+            .await
+    }
+
+    pub async fn list_markets(&self, market_filter: &MarketFilter) -> Result<Vec<GammaMarket>> {
+        self.get_all_pages("markets", market_filter.query()).await
+    }
+
+    async fn get_all_pages<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: Vec<(&'static str, String)>,
+    ) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        loop {
+            let mut page_query = query.clone();
+            page_query.push(("limit", self.page_size.to_string()));
+            page_query.push(("offset", items.len().to_string()));
+            let page: Vec<T> = self.rest_client.get(path, &page_query).await?;
+            let page_size = page.len();
+            items.extend(page);
+            if page_size < self.page_size {
+                return Ok(items);
+            }
+        }
+    }
+}
+
+impl GammaMarket {
+    pub fn daily_rate(&self) -> Decimal {
+        self.clob_rewards
+            .iter()
+            .map(|reward| reward.rewards_daily_rate)
+            .sum()
+    }
+
+    pub fn event_slug(&self) -> Option<&String> {
+        self.events.first().map(|event| &event.slug)
+    }
+
+    pub fn event_start(&self) -> Result<Option<DateTime<Utc>>> {
+        let game_start = self
+            .game_start_time
+            .as_deref()
+            .map(parse_gamma_time)
+            .transpose()?;
+        Ok(game_start.or(self.events.first().and_then(|event| event.start_date)))
+    }
+
+    pub fn outcomes(&self) -> Result<Vec<String>> {
+        let outcomes = self
+            .outcomes
+            .as_deref()
+            .with_context(|| format!("Market {} has no outcomes", self.slug))?;
+        serde_json::from_str(outcomes).context("failed to parse outcomes json")
+    }
+
+    pub fn default_primary_outcome(&self) -> Result<String> {
+        let outcomes = self.outcomes()?;
+        outcomes
+            .iter()
+            .find(|outcome| outcome.eq_ignore_ascii_case(DEFAULT_PRIMARY_OUTCOME))
+            .or(outcomes.first())
+            .cloned()
+            .with_context(|| format!("Market {} has no outcomes", self.slug))
+    }
+
+    pub fn token_ids(&self, primary_outcome: &str) -> Result<TokenIds> {
+        let clob_token_ids = self
+            .clob_token_ids
+            .as_deref()
+            .with_context(|| format!("Market {} has no clob token ids", self.slug))?;
+        let token_vec: Vec<U256> =
+            serde_json::from_str(clob_token_ids).context("failed to parse token vec json")?;
+        let outcome_vec = self.outcomes()?;
+        let ([first_token, second_token], [first_outcome, second_outcome]) =
+            (&token_vec[..], &outcome_vec[..])
+        else {
+            bail!("Market {} does not have two outcome tokens", self.slug);
+        };
+
+        if first_outcome.eq_ignore_ascii_case(primary_outcome) {
+            Ok(TokenIds {
+                buy_token: *first_token,
+                sell_token: *second_token,
+            })
+        } else if second_outcome.eq_ignore_ascii_case(primary_outcome) {
+            Ok(TokenIds {
+                buy_token: *second_token,
+                sell_token: *first_token,
+            })
+        } else {
+            bail!(
+                "Market {} has outcomes {first_outcome}/{second_outcome} and none matches primary outcome {primary_outcome}, set `primary_outcome`",
+                self.slug
+            );
+        }
+    }
+}
+
+fn parse_gamma_time(time: &str) -> Result<DateTime<Utc>> {
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(time) {
+        return Ok(date_time.with_timezone(&Utc));
+    }
+    let naive_time = time.trim_end_matches("+00").trim_end_matches('Z');
+    let date_time = NaiveDateTime::parse_from_str(naive_time, "%Y-%m-%d %H:%M:%S")
+        .with_context(|| format!("Invalid gamma timestamp: {}", time))?;
+    Ok(date_time.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    const CONDITION_ID: &str = "0x0000000000000000000000000000000000000000000000000000000000000001";
+
+    fn market_json(slug: &str, outcomes: &str) -> String {
+        format!(
+            r#"{{"slug": "{slug}", "question": "{slug}?", "conditionId": "{CONDITION_ID}", "clobTokenIds": "[\"11\", \"22\"]", "outcomes": "{}"}}"#,
+            outcomes.replace('"', "\\\"")
+        )
+    }
+
+    fn serve(responses: Vec<(u16, String)>) -> (GammaClient, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let gamma_config = GammaConfig {
+            base_url: format!("http://{}", listener.local_addr().unwrap()),
+            page_size: 1,
+        };
+        let http_config = HttpConfig {
+            retry_backoff_ms: 1,
+            ..HttpConfig::default()
+        };
+        let handle = thread::spawn(move || {
+            let mut request_lines = Vec::new();
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buffer = [0; 4096];
+                let read = stream.read(&mut buffer).unwrap();
+                let request = String::from_utf8_lossy(&buffer[..read]).to_string();
+                request_lines.push(request.lines().next().unwrap_or_default().to_string());
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} Status\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+            request_lines
+        });
+        (
+            GammaClient::new(&gamma_config, &http_config).unwrap(),
+            handle,
+        )
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_and_fails_on_client_errors() {
+        let event = format!(
+            r#"{{"slug": "event", "markets": [{}]}}"#,
+            market_json("market", r#"["Yes", "No"]"#)
+        );
+        let (gamma_client, handle) = serve(vec![
+            (503, String::new()),
+            (200, event),
+            (404, String::new()),
+        ]);
+
+        let event = gamma_client.event_by_slug("event").await.unwrap();
+        assert_eq!(event.markets[0].slug, "market");
+        assert!(gamma_client.market_by_slug("missing").await.is_err());
+
+        let request_lines = handle.join().unwrap();
+        assert_eq!(request_lines.len(), 3);
+        assert!(request_lines[2].starts_with("GET /markets/slug/missing"));
+    }
+
+    #[tokio::test]
+    async fn list_markets_follows_pages() {
+        let (gamma_client, handle) = serve(vec![
+            (
+                200,
+                format!("[{}]", market_json("first", r#"["Yes", "No"]"#)),
+            ),
+            (
+                200,
+                format!("[{}]", market_json("second", r#"["No", "Yes"]"#)),
+            ),
+            (200, "[]".to_string()),
+        ]);
+        let market_filter = MarketFilter {
+            active: Some(true),
+            closed: Some(false),
+        };
+
+        let markets = gamma_client.list_markets(&market_filter).await.unwrap();
+        assert_eq!(markets.len(), 2);
+        let request_lines = handle.join().unwrap();
+        assert!(request_lines[1].contains("active=true&closed=false&limit=1&offset=1"));
+
+        let token_ids = markets[1].token_ids("yes").unwrap();
+        assert_eq!(token_ids.buy_token, U256::from(22));
+        assert_eq!(token_ids.sell_token, U256::from(11));
+        assert!(markets[0].token_ids("Lakers").is_err());
+    }
+}
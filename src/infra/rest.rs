@@ -0,0 +1,96 @@
+use anyhow::{Context, Result, anyhow};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct RestClient {
+    name: &'static str,
+    http: reqwest::Client,
+    base_url: String,
+    max_retries: u32,
+    retry_backoff: Duration,
+}
+
+enum RequestError {
+    Retryable(anyhow::Error),
+    Fatal(anyhow::Error),
+}
+
+impl RestClient {
+    pub fn new(
+        name: &'static str,
+        base_url: &str,
+        timeout: Duration,
+        max_retries: u32,
+        retry_backoff: Duration,
+    ) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .with_context(|| format!("Failed to build {name} http client"))?;
+        Ok(Self {
+            name,
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            max_retries,
+            retry_backoff,
+        })
+    }
+
+    pub async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T> {
+        let url = format!("{}/{}", self.base_url, path);
+        let mut attempt = 0;
+        loop {
+            match self.try_get(&url, query).await {
+                Ok(response) => return Ok(response),
+                Err(RequestError::Retryable(e)) if attempt < self.max_retries => {
+                    let backoff = self
+                        .retry_backoff
+                        .saturating_mul(2u32.saturating_pow(attempt));
+                    eprintln!(
+                        "{} request to {path} failed, retrying in {backoff:?}: {e}",
+                        self.name
+                    );
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+                Err(RequestError::Retryable(e) | RequestError::Fatal(e)) => {
+                    return Err(e.context(format!("{} request to {path} failed", self.name)));
+                }
+            }
+        }
+    }
+
+    async fn try_get<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, RequestError> {
+        let response = self
+            .http
+            .get(url)
+            .query(query)
+            .send()
+            .await
+            .map_err(|e| RequestError::Retryable(e.into()))?;
+        let status = response.status();
+        if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(RequestError::Retryable(anyhow!("status {status}")));
+        }
+        if !status.is_success() {
+            return Err(RequestError::Fatal(anyhow!("status {status}")));
+        }
+        let body = response
+            .text()
+            .await
+            .map_err(|e| RequestError::Retryable(e.into()))?;
+        serde_json::from_str(&body).map_err(|e| {
+            RequestError::Fatal(anyhow!(e).context(format!("Invalid {} response", self.name)))
+        })
+    }
+}
@@ -1,5 +1,8 @@
 pub mod market;
 
 pub mod market_types;
+pub mod order_book;
+pub mod price_history;
+pub mod protection;
 
 pub use market::Market;
@@ -1,94 +1,511 @@
-use crate::infra::config::MarketConfig;
-use crate::infra::get_token_id;
+use crate::infra::config::{GroupConfig, MarketConfig, MarketSelector, PricingMode, SkewShape};
+use crate::infra::{GammaClient, get_market_infos};
 use crate::market_logic::market_types::{
-    NewPrices, OpenOrder, OpenOrderStatus, Order, OrderRequest, Spreads,
+    MarketStatus, NewPrices, OpenOrder, OpenOrderStatus, Order, OrderRequest, QuotePrices,
+    RequoteCheck, SizeFactors, Spreads,
 };
-use crate::types::TokenIds;
-use crate::types::channel_types::{OrderSide, OrderUpdate, PlacedOrder};
+use crate::market_logic::order_book::OrderBook;
+use crate::market_logic::price_history::PriceHistory;
+use crate::market_logic::protection::Protection;
+use crate::types::channel_types::{
+    BookChange, BookSnapshot, LifecycleEvent, OrderSide, OrderUpdate, PlacedOrder, RejectedOrder,
+};
+use crate::types::{MarketInfo, TokenIds};
 use alloy::primitives::{B256, U256};
-use anyhow::Result;
+use anyhow::{Result, bail};
+use chrono::{DateTime, TimeDelta, Utc};
 use polymarket_client_sdk::types::Decimal;
+use std::cmp::Ordering;
+use std::time::{Duration, Instant};
+
+// The CLOB only accepts GTD expirations at least one minute in the future.
+const GTD_SECURITY_THRESHOLD_SECS: i64 = 60;
 
 pub struct CheckOrderResult {
     place: Option<Order>,
     cancel: Option<String>,
 }
 
+#[derive(Debug)]
 pub struct Market {
+    pub slug: String,
     pub token_ids: TokenIds,
-    pub bid_order: Option<OpenOrder>,
-    pub ask_order: Option<OpenOrder>,
+    pub bid_orders: Vec<Option<OpenOrder>>,
+    pub ask_orders: Vec<Option<OpenOrder>>,
     pub exposure: Decimal,
+    pub tick_size: Decimal,
+    pub min_order_size: Decimal,
+    pub price_history: PriceHistory,
+    pub order_book: OrderBook,
+    pub protection: Protection,
+    pub event_start: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub neg_risk: bool,
+    pub retiring: bool,
+    pub flatten_on_retire: bool,
+    pub status: MarketStatus,
+    pub last_trade_price: Option<Decimal>,
+    pub group: Option<String>,
+    pub group_config: Option<GroupConfig>,
+    pub group_exposure: Decimal,
     pub config: MarketConfig,
 }
 
 impl Market {
-    pub async fn new(config: MarketConfig) -> Result<(Self, B256, String)> {
-        let (token_ids, market_identifier) = get_token_id(&config.slug).await?;
-        let market_slug = config.slug.clone();
+    pub async fn from_config(
+        config: MarketConfig,
+        gamma_client: &GammaClient,
+    ) -> Result<Vec<(Self, B256, String)>> {
+        let market_infos = get_market_infos(
+            gamma_client,
+            &config.identifier()?,
+            config.primary_outcome.as_deref(),
+        )
+        .await?;
+        let expand = config.market == MarketSelector::All;
+        market_infos
+            .into_iter()
+            .filter(|market_info| !expand || (!market_info.closed && market_info.accepting_orders))
+            .map(|market_info| Self::new(config.clone(), market_info))
+            .collect()
+    }
+
+    pub fn new(config: MarketConfig, market_info: MarketInfo) -> Result<(Self, B256, String)> {
+        if market_info.closed || !market_info.accepting_orders {
+            bail!(
+                "Market {} is closed or not accepting orders",
+                market_info.slug
+            );
+        }
+        let level_count = config.quote_levels().len();
+        let price_history = PriceHistory::new(config.price_history_window());
 
         let market = Self {
-            token_ids,
-            bid_order: None,
-            ask_order: None,
-            config,
+            slug: market_info.slug.clone(),
+            token_ids: market_info.token_ids,
+            bid_orders: (0..level_count).map(|_| None).collect(),
+            ask_orders: (0..level_count).map(|_| None).collect(),
             exposure: Decimal::from(0),
+            tick_size: market_info.tick_size.unwrap_or(Decimal::new(1, 2)),
+            min_order_size: market_info.min_order_size.unwrap_or(Decimal::from(5)),
+            price_history,
+            order_book: OrderBook::new(),
+            protection: Protection::default(),
+            event_start: market_info.event_start,
+            end_date: market_info.end_date,
+            neg_risk: market_info.neg_risk,
+            retiring: false,
+            flatten_on_retire: false,
+            status: MarketStatus::Active,
+            last_trade_price: None,
+            group: config.group.clone().or(market_info.event_slug),
+            group_config: None,
+            group_exposure: Decimal::from(0),
+            config,
         };
-        Ok((market, market_identifier, market_slug))
+        Ok((market, market_info.condition_id, market_info.slug))
     }
 
-    fn get_spreads(&self) -> Spreads {
-        let ask = if self.exposure > self.config.max_exposure {
-            Decimal::from(0)
+    fn skew_ratio(&self, exposure: Decimal, max_exposure: Decimal) -> Decimal {
+        let ratio = if max_exposure > Decimal::from(0) {
+            (exposure / max_exposure).clamp(Decimal::from(-1), Decimal::from(1))
         } else {
-            self.config.spread
+            match exposure.cmp(&Decimal::from(0)) {
+                Ordering::Greater => Decimal::from(1),
+                Ordering::Less => Decimal::from(-1),
+                Ordering::Equal => Decimal::from(0),
+            }
         };
+        match self.config.skew.shape {
+            SkewShape::Linear => ratio,
+            SkewShape::Quadratic => ratio * ratio.abs(),
+        }
+    }
 
-        let bid = if self.exposure < -self.config.max_exposure {
-            Decimal::from(0)
-        } else {
-            self.config.spread
+    fn inventory_skew(&self) -> Decimal {
+        self.skew_ratio(self.exposure, self.config.max_exposure)
+    }
+
+    fn group_skew(&self) -> Decimal {
+        self.group_config
+            .as_ref()
+            .map_or(Decimal::from(0), |group_config| {
+                self.skew_ratio(self.group_exposure, group_config.max_exposure)
+            })
+    }
+
+    fn base_spread(&self) -> Decimal {
+        let spread = match &self.config.volatility {
+            Some(volatility) => (volatility.min_spread
+                + volatility.multiplier * self.price_history.price_range())
+            .clamp(volatility.min_spread, volatility.max_spread),
+            None => self.config.spread,
+        };
+        let spread_multiplier = self
+            .config
+            .active_blackout(Utc::now(), self.event_start)
+            .and_then(|blackout| blackout.spread_multiplier)
+            .unwrap_or(Decimal::from(1));
+        spread * spread_multiplier
+    }
+
+    fn get_spreads(&self) -> Spreads {
+        let base_spread = self.base_spread();
+        let max_skew = self.config.skew.max_skew.unwrap_or(base_spread);
+        let group_max_skew = self
+            .group_config
+            .as_ref()
+            .and_then(|group_config| group_config.max_skew)
+            .unwrap_or(base_spread);
+        let skew = self.inventory_skew() * max_skew + self.group_skew() * group_max_skew;
+        let min_spread = base_spread.min(self.tick_size);
+
+        Spreads {
+            bid: (base_spread + skew).max(min_spread),
+            ask: (base_spread - skew).max(min_spread),
+        }
+    }
+
+    fn get_size_factors(&self) -> SizeFactors {
+        let group_max_size_skew = self
+            .group_config
+            .as_ref()
+            .map_or(Decimal::from(0), |group_config| group_config.max_size_skew);
+        let size_skew = self.inventory_skew() * self.config.skew.max_size_skew
+            + self.group_skew() * group_max_size_skew;
+
+        SizeFactors {
+            bid: (Decimal::from(1) - size_skew).min(Decimal::from(1)),
+            ask: (Decimal::from(1) + size_skew).min(Decimal::from(1)),
+        }
+    }
+
+    fn get_quote_prices(&self, new_prices: &NewPrices, spreads: &Spreads) -> QuotePrices {
+        let (bid, ask) = match self.config.pricing_mode {
+            PricingMode::BestPrice => (
+                new_prices.best_bid - spreads.bid,
+                new_prices.best_ask + spreads.ask,
+            ),
+            PricingMode::Midpoint => {
+                let midpoint = new_prices.midpoint();
+                (midpoint - spreads.bid, midpoint + spreads.ask)
+            }
+            PricingMode::Depth => {
+                let depth_bid = self.config.depth.price(self.order_book.bids());
+                let depth_ask = self.config.depth.price(self.order_book.asks());
+                (
+                    depth_bid.unwrap_or(new_prices.best_bid) - spreads.bid,
+                    depth_ask.unwrap_or(new_prices.best_ask) + spreads.ask,
+                )
+            }
         };
+        let mut bid = (bid / self.tick_size).floor() * self.tick_size;
+        let mut ask = (ask / self.tick_size).ceil() * self.tick_size;
+        if self.config.pricing_mode == PricingMode::Depth
+            && let Some(max_spread) = self.config.reward_max_spread
+        {
+            let midpoint = new_prices.midpoint();
+            let lowest_bid = ((midpoint - max_spread) / self.tick_size).ceil() * self.tick_size;
+            let highest_ask = ((midpoint + max_spread) / self.tick_size).floor() * self.tick_size;
+            bid = bid.max(lowest_bid);
+            ask = ask.min(highest_ask);
+        }
+        QuotePrices {
+            bid: bid.min(new_prices.best_ask - self.tick_size),
+            ask: ask.max(new_prices.best_bid + self.tick_size),
+        }
+    }
+
+    pub fn book_snapshot(&mut self, snapshot: BookSnapshot) -> Option<Vec<OrderRequest>> {
+        self.order_book.apply_snapshot(snapshot);
+        self.book_update()
+    }
+
+    pub fn book_change(&mut self, change: BookChange) -> Option<Vec<OrderRequest>> {
+        if let Some(protection_config) = &self.config.protection
+            && self.order_book.clears_best_level(&change)
+        {
+            self.protection
+                .record_level_cleared(protection_config, Instant::now());
+        }
+        self.order_book.apply_change(change);
+        self.book_update()
+    }
 
-        Spreads { bid, ask }
+    pub fn tick(&mut self) -> Option<Vec<OrderRequest>> {
+        self.book_update()
+    }
+
+    fn book_update(&mut self) -> Option<Vec<OrderRequest>> {
+        // Quotes priced off a book that has drifted are pulled until a fresh
+        // snapshot arrives.
+        if self.order_book.is_stale() {
+            return self.pull_quotes();
+        }
+        let new_prices = self.order_book.new_prices()?;
+        self.price_update(new_prices)
     }
 
     pub fn price_update(&mut self, new_prices: NewPrices) -> Option<Vec<OrderRequest>> {
+        let mut order_requests = self.quote(new_prices)?;
+        let expiration = self.config.gtd.as_ref().map(|gtd| {
+            Utc::now()
+                + TimeDelta::seconds(GTD_SECURITY_THRESHOLD_SECS)
+                + TimeDelta::seconds(gtd.expiration_secs as i64)
+        });
+        for order_request in &mut order_requests {
+            if let OrderRequest::PlaceOrder(order) = order_request {
+                order.expiration = expiration;
+                order.post_only &= self.config.post_only;
+                order.neg_risk = self.neg_risk;
+            }
+        }
+        Some(order_requests)
+    }
+
+    fn quote(&mut self, new_prices: NewPrices) -> Option<Vec<OrderRequest>> {
         let mut order_requests = Vec::new();
+        let now = Instant::now();
+        self.price_history.push(now, new_prices.midpoint());
+        if self.retiring && self.flatten_on_retire && self.status == MarketStatus::Active {
+            return self.flatten(&new_prices);
+        }
+        if self.retiring || self.status != MarketStatus::Active {
+            return self.pull_quotes();
+        }
+        if self.quoting_ended(Utc::now()) {
+            return if self.config.flatten_before_end {
+                self.flatten(&new_prices)
+            } else {
+                self.pull_quotes()
+            };
+        }
+        if let Some(protection_config) = &self.config.protection
+            && self.protection.is_triggered(
+                protection_config,
+                now,
+                new_prices.midpoint(),
+                &self.order_book,
+            )
+        {
+            return self.pull_quotes();
+        }
+        if self
+            .config
+            .active_blackout(Utc::now(), self.event_start)
+            .is_some_and(|blackout| blackout.spread_multiplier.is_none())
+        {
+            return self.pull_quotes();
+        }
         let spreads = self.get_spreads();
-        let desired_bid_price = new_prices.best_bid - spreads.bid;
-        let desired_ask_price = Decimal::from(1) - new_prices.best_ask - spreads.ask;
-        let size = self.config.order_size;
-
-        let check_bid_result = Self::check_order(
-            &mut self.bid_order,
-            desired_bid_price,
-            size,
-            self.token_ids.buy_token,
-        );
-        let check_ask_result = Self::check_order(
-            &mut self.ask_order,
-            desired_ask_price,
-            size,
-            self.token_ids.sell_token,
+        let quote_prices = self.get_quote_prices(&new_prices, &spreads);
+        let size_factors = self.get_size_factors();
+        let midpoint = new_prices.midpoint();
+        let bid_requote_check = self.requote_check(midpoint, new_prices.best_ask);
+        let ask_requote_check = self.requote_check(
+            Decimal::from(1) - midpoint,
+            Decimal::from(1) - new_prices.best_bid,
         );
 
-        if let Some(ask_result) = check_ask_result {
-            if let Some(order_id) = ask_result.cancel {
-                order_requests.push(OrderRequest::CancelOrder(order_id))
+        let mut desired_bids = Vec::new();
+        let mut desired_asks = Vec::new();
+        let highest_bid = bid_requote_check.cross_price - self.tick_size;
+        let highest_ask = ask_requote_check.cross_price - self.tick_size;
+        // Levels skewed below the exchange minimum are dropped rather than
+        // posted and rejected.
+        let level_size = |size: Decimal| {
+            let size = size.round_dp(2);
+            if size < self.min_order_size {
+                Decimal::from(0)
+            } else {
+                size
             }
-            if let Some(order) = ask_result.place {
-                order_requests.push(OrderRequest::PlaceOrder(order))
+        };
+        // Both sides buy a token, so level prices are floored to the tick to
+        // stay on the passive side of an off-tick offset.
+        let floor_to_tick = |price: Decimal| (price / self.tick_size).floor() * self.tick_size;
+        for quote_level in self.config.quote_levels() {
+            desired_bids.push(Order::new(
+                floor_to_tick(quote_prices.bid - quote_level.offset).min(highest_bid),
+                level_size(quote_level.size * size_factors.bid),
+                self.token_ids.buy_token,
+            ));
+            desired_asks.push(Order::new(
+                floor_to_tick(Decimal::from(1) - quote_prices.ask - quote_level.offset)
+                    .min(highest_ask),
+                level_size(quote_level.size * size_factors.ask),
+                self.token_ids.sell_token,
+            ));
+        }
+        // Levels that land on the price of an earlier level are dropped, so no
+        // two slots of a ladder rest at the same price.
+        for desired_orders in [&mut desired_bids, &mut desired_asks] {
+            for level in 1..desired_orders.len() {
+                let price = desired_orders[level].price;
+                if desired_orders[..level]
+                    .iter()
+                    .any(|order| order.size > Decimal::from(0) && order.price == price)
+                {
+                    desired_orders[level].size = Decimal::from(0);
+                }
             }
         }
 
-        if let Some(bid_result) = check_bid_result {
-            if let Some(order_id) = bid_result.cancel {
-                order_requests.push(OrderRequest::CancelOrder(order_id));
+        if new_prices.requires_two_sided() {
+            let min_size = self.config.reward_min_size;
+            let bid_scores = desired_bids
+                .iter()
+                .any(|order| bid_requote_check.scores(order, min_size));
+            let ask_scores = desired_asks
+                .iter()
+                .any(|order| ask_requote_check.scores(order, min_size));
+            if !bid_scores || !ask_scores {
+                return self.pull_quotes();
+            }
+        }
+
+        for (level, (desired_bid, desired_ask)) in
+            desired_bids.into_iter().zip(desired_asks).enumerate()
+        {
+            let check_bid_result = Self::check_order(
+                &mut self.bid_orders[level],
+                desired_bid.price,
+                desired_bid.size,
+                desired_bid.token_id,
+                &bid_requote_check,
+            );
+            let check_ask_result = Self::check_order(
+                &mut self.ask_orders[level],
+                desired_ask.price,
+                desired_ask.size,
+                desired_ask.token_id,
+                &ask_requote_check,
+            );
+
+            Self::push_order_requests(&mut order_requests, check_ask_result);
+            Self::push_order_requests(&mut order_requests, check_bid_result);
+        }
+
+        if order_requests.is_empty() {
+            None
+        } else {
+            Some(order_requests)
+        }
+    }
+
+    pub fn retire(&mut self, flatten: bool) -> Option<Vec<OrderRequest>> {
+        self.retiring = true;
+        self.flatten_on_retire = flatten;
+        self.pull_quotes()
+    }
+
+    pub fn is_drained(&self) -> bool {
+        let flat = !self.flatten_on_retire || self.exposure.abs().round_dp(2) == Decimal::from(0);
+        ((self.retiring && flat) || self.status == MarketStatus::Stopped)
+            && self
+                .bid_orders
+                .iter()
+                .chain(self.ask_orders.iter())
+                .all(Option::is_none)
+    }
+
+    pub fn lifecycle_update(&mut self, event: LifecycleEvent) -> Option<Vec<OrderRequest>> {
+        let last_trade = self
+            .last_trade_price
+            .map_or("none".to_string(), |price| price.to_string());
+        match event {
+            LifecycleEvent::Resolved | LifecycleEvent::Closed => {
+                if self.status != MarketStatus::Stopped {
+                    println!(
+                        "Market {} {:?}, stopping. Last trade: {last_trade}",
+                        self.slug, event
+                    );
+                }
+                self.status = MarketStatus::Stopped;
+                self.pull_quotes()
+            }
+            LifecycleEvent::TradingPaused => {
+                if self.status == MarketStatus::Active {
+                    println!(
+                        "Market {} paused trading. Last trade: {last_trade}",
+                        self.slug
+                    );
+                    self.status = MarketStatus::Paused;
+                }
+                self.pull_quotes()
+            }
+            LifecycleEvent::TradingResumed => {
+                if self.status == MarketStatus::Paused {
+                    println!("Market {} resumed trading", self.slug);
+                    self.status = MarketStatus::Active;
+                }
+                None
             }
-            if let Some(order) = bid_result.place {
-                order_requests.push(OrderRequest::PlaceOrder(order))
+            LifecycleEvent::TickSizeChanged(tick_size) => {
+                if tick_size != self.tick_size {
+                    println!(
+                        "Market {} tick size changed from {} to {tick_size}",
+                        self.slug, self.tick_size
+                    );
+                    self.tick_size = tick_size;
+                }
+                None
+            }
+            LifecycleEvent::LastTradePrice(price) => {
+                self.last_trade_price = Some(price);
+                None
+            }
+        }
+    }
+
+    pub fn resize(&mut self, order_size: Decimal, max_exposure: Decimal) {
+        self.config.order_size = order_size;
+        self.config.max_exposure = max_exposure;
+        self.retiring = false;
+        self.flatten_on_retire = false;
+    }
+
+    pub fn reconfigure(&mut self, market: Market) -> Result<Option<Vec<OrderRequest>>> {
+        // Exposure and resting orders are tracked against the primary outcome,
+        // so a reload that flips it cannot be applied to a running market.
+        if market.token_ids != self.token_ids {
+            bail!(
+                "Primary outcome of {} changed, restart the bot to apply it",
+                self.slug
+            );
+        }
+        let level_count = market.config.quote_levels().len();
+        let mut order_requests = Vec::new();
+        for ladder in [&mut self.bid_orders, &mut self.ask_orders] {
+            for open_order in ladder.iter_mut().skip(level_count) {
+                Self::cancel_placed_order(open_order, &mut order_requests);
             }
+            ladder.resize_with(level_count, || None);
+        }
+        self.price_history
+            .set_window(market.config.price_history_window());
+        self.retiring = false;
+        self.flatten_on_retire = false;
+        self.slug = market.slug;
+        self.tick_size = market.tick_size;
+        self.min_order_size = market.min_order_size;
+        self.neg_risk = market.neg_risk;
+        self.event_start = market.event_start;
+        self.end_date = market.end_date;
+        self.group = market.group;
+        self.config = market.config;
+
+        if order_requests.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(order_requests))
+        }
+    }
+
+    fn pull_quotes(&mut self) -> Option<Vec<OrderRequest>> {
+        let mut order_requests = Vec::new();
+        for open_order in self.bid_orders.iter_mut().chain(self.ask_orders.iter_mut()) {
+            Self::cancel_placed_order(open_order, &mut order_requests);
         }
 
         if order_requests.is_empty() {
@@ -98,23 +515,135 @@ impl Market {
         }
     }
 
+    fn cancel_placed_order(
+        open_order: &mut Option<OpenOrder>,
+        order_requests: &mut Vec<OrderRequest>,
+    ) {
+        if let Some(OpenOrder {
+            status: OpenOrderStatus::Placed(order_id),
+            ..
+        }) = open_order
+        {
+            order_requests.push(OrderRequest::CancelOrder(order_id.clone()));
+            *open_order = None;
+        }
+    }
+
+    fn quoting_ended(&self, now: DateTime<Utc>) -> bool {
+        match (self.end_date, self.config.stop_before_end_mins) {
+            (Some(end_date), Some(stop_before_end_mins)) => {
+                now >= end_date - TimeDelta::minutes(stop_before_end_mins)
+            }
+            _ => false,
+        }
+    }
+
+    fn flatten(&mut self, new_prices: &NewPrices) -> Option<Vec<OrderRequest>> {
+        let size = self.exposure.abs().round_dp(2);
+        if size == Decimal::from(0) {
+            return self.pull_quotes();
+        }
+        let (flatten_orders, other_orders, price, token_id) = if self.exposure > Decimal::from(0) {
+            (
+                &mut self.ask_orders,
+                &mut self.bid_orders,
+                Decimal::from(1) - new_prices.best_bid,
+                self.token_ids.sell_token,
+            )
+        } else {
+            (
+                &mut self.bid_orders,
+                &mut self.ask_orders,
+                new_prices.best_ask,
+                self.token_ids.buy_token,
+            )
+        };
+
+        let mut order_requests = Vec::new();
+        for open_order in other_orders
+            .iter_mut()
+            .chain(flatten_orders.iter_mut().skip(1))
+        {
+            Self::cancel_placed_order(open_order, &mut order_requests);
+        }
+        let flatten_check = RequoteCheck {
+            min_move: self.tick_size,
+            min_age: Duration::from_millis(0),
+            midpoint: price,
+            cross_price: Decimal::from(2),
+            max_spread: None,
+            refresh_after: self.config.gtd_refresh_after(),
+        };
+        let mut check_flatten_result = Self::check_order(
+            &mut flatten_orders[0],
+            price,
+            size,
+            token_id,
+            &flatten_check,
+        );
+        // Flatten orders take liquidity on purpose, posting them post-only would
+        // get every one of them rejected.
+        if let Some(CheckOrderResult {
+            place: Some(order), ..
+        }) = &mut check_flatten_result
+        {
+            order.post_only = false;
+        }
+        Self::push_order_requests(&mut order_requests, check_flatten_result);
+
+        if order_requests.is_empty() {
+            None
+        } else {
+            Some(order_requests)
+        }
+    }
+
+    fn requote_check(&self, midpoint: Decimal, cross_price: Decimal) -> RequoteCheck {
+        RequoteCheck {
+            min_move: self.tick_size * Decimal::from(self.config.requote.min_move_ticks),
+            min_age: Duration::from_millis(self.config.requote.min_order_age_ms),
+            midpoint,
+            cross_price,
+            max_spread: self.config.reward_max_spread,
+            refresh_after: self.config.gtd_refresh_after(),
+        }
+    }
+
+    fn push_order_requests(
+        order_requests: &mut Vec<OrderRequest>,
+        check_order_result: Option<CheckOrderResult>,
+    ) {
+        if let Some(result) = check_order_result {
+            if let Some(order_id) = result.cancel {
+                order_requests.push(OrderRequest::CancelOrder(order_id));
+            }
+            if let Some(order) = result.place {
+                order_requests.push(OrderRequest::PlaceOrder(order));
+            }
+        }
+    }
+
     pub fn check_order(
         open_order: &mut Option<OpenOrder>,
         desired_price: Decimal,
         size: Decimal,
         token_id: U256,
+        requote_check: &RequoteCheck,
     ) -> Option<CheckOrderResult> {
+        let quotable = size > Decimal::from(0)
+            && desired_price <= Decimal::new(99, 2)
+            && desired_price >= Decimal::new(1, 2);
         let place_order = match &open_order {
             Some(order) => match &order.status {
                 OpenOrderStatus::Pending => None,
                 OpenOrderStatus::Placed(order_id) => {
-                    if desired_price > Decimal::new(99, 2) || desired_price < Decimal::new(1, 2) {
+                    if !quotable {
                         return Some(CheckOrderResult {
                             place: None,
                             cancel: Some(order_id.clone()),
                         });
                     };
-                    if order.price != desired_price {
+                    if requote_check.should_requote(order, desired_price, size) {
                         let new_order = Order::new(desired_price, size, token_id);
                         Some(CheckOrderResult {
                             place: Some(new_order),
@@ -125,6 +654,7 @@ impl Market {
                     }
                 }
             },
+            None if !quotable => None,
             None => {
                 let new_order = Order::new(desired_price, size, token_id);
                 Some(CheckOrderResult {
@@ -134,42 +664,82 @@ impl Market {
             }
         };
         if place_order.is_some() {
-            *open_order = Some(OpenOrder::default(desired_price, OpenOrderStatus::Pending));
+            *open_order = Some(OpenOrder::default(
+                desired_price,
+                size,
+                OpenOrderStatus::Pending,
+            ));
         }
         place_order
     }
 
-    pub fn order_placed_update(&mut self, placed_order: PlacedOrder) -> Option<String> {
-        let mut cancel_order_id: Option<String> = None;
+    pub fn order_rejected(&mut self, rejected_order: RejectedOrder) {
+        let ladder = if rejected_order.token_id == self.token_ids.buy_token {
+            &mut self.bid_orders
+        } else {
+            &mut self.ask_orders
+        };
+        if let Some(open_order) = ladder.iter_mut().find(|open_order| {
+            matches!(open_order, Some(order) if order.status == OpenOrderStatus::Pending && order.price == rejected_order.price)
+        }) {
+            *open_order = None;
+        }
+    }
 
-        let open_order = if placed_order.token_id == self.token_ids.buy_token {
-            &mut self.bid_order
+    pub fn order_placed_update(&mut self, placed_order: PlacedOrder) -> Option<String> {
+        let quote_levels = self.config.quote_levels();
+        let ladder = if placed_order.token_id == self.token_ids.buy_token {
+            &mut self.bid_orders
         } else {
-            &mut self.ask_order
+            &mut self.ask_orders
         };
 
+        // A pending slot at the placed price is the one waiting for this order;
+        // a placed slot at the same price already holds a live order of ours.
+        let matching_level = ladder
+            .iter()
+            .position(|open_order| {
+                matches!(open_order, Some(order) if order.price == placed_order.price
+                    && order.status == OpenOrderStatus::Pending)
+            })
+            .or_else(|| {
+                ladder.iter().position(
+                    |open_order| matches!(open_order, Some(order) if order.price == placed_order.price),
+                )
+            })
+            .or_else(|| {
+                ladder.iter().position(|open_order| {
+                    matches!(open_order, Some(order) if order.status == OpenOrderStatus::Pending)
+                })
+            })
+            .or_else(|| ladder.iter().position(Option::is_none));
+
+        let Some(level) = matching_level else {
+            return Some(placed_order.order_id);
+        };
+        let open_order = &mut ladder[level];
+
         match open_order {
             Some(order) => {
-                match &order.status {
-                    OpenOrderStatus::Pending => {}
-                    OpenOrderStatus::Placed(order_id) => {
-                        cancel_order_id = Some(order_id.clone());
-                    }
-                }
+                let cancel_order_id = match &order.status {
+                    OpenOrderStatus::Pending => None,
+                    OpenOrderStatus::Placed(order_id) => Some(order_id.clone()),
+                };
                 if order.price == placed_order.price {
                     order.status = OpenOrderStatus::Placed(placed_order.order_id);
+                    order.updated_at = Instant::now();
                 } else {
-                    *open_order = Self::create_placed_order(placed_order);
+                    *open_order = Self::create_placed_order(placed_order, quote_levels[level].size);
                 }
                 cancel_order_id
             }
             None => {
-                *open_order = Self::create_placed_order(placed_order);
+                *open_order = Self::create_placed_order(placed_order, quote_levels[level].size);
                 None
             }
         }
     }
-    fn check_order_id(open_order: &Option<OpenOrder>, order_id: &String) -> bool {
+    fn check_order_id(open_order: &Option<OpenOrder>, order_id: &str) -> bool {
         if let Some(order) = open_order {
             match &order.status {
                 OpenOrderStatus::Pending => false,
@@ -179,53 +749,57 @@ impl Market {
             false
         }
     }
-    fn get_order_side_from_id(&self, order_id: &String) -> Option<OrderSide> {
-        if Self::check_order_id(&self.bid_order, order_id) {
-            Some(OrderSide::Buy)
-        } else if Self::check_order_id(&self.ask_order, order_id) {
-            Some(OrderSide::Sell)
+    fn get_order_side_from_id(&self, order_id: &str) -> Option<(OrderSide, usize)> {
+        if let Some(level) = Self::find_level(&self.bid_orders, order_id) {
+            Some((OrderSide::Buy, level))
         } else {
-            None
+            Self::find_level(&self.ask_orders, order_id).map(|level| (OrderSide::Sell, level))
         }
     }
-    fn create_placed_order(placed_order: PlacedOrder) -> Option<OpenOrder> {
+    fn find_level(ladder: &[Option<OpenOrder>], order_id: &str) -> Option<usize> {
+        ladder
+            .iter()
+            .position(|open_order| Self::check_order_id(open_order, order_id))
+    }
+    fn create_placed_order(placed_order: PlacedOrder, size: Decimal) -> Option<OpenOrder> {
         Some(OpenOrder::default(
             placed_order.price,
+            size,
             OpenOrderStatus::Placed(placed_order.order_id),
         ))
     }
     pub fn cancelled_order_update(&mut self, order_id: String) {
         let order = self.get_order_side_from_id(&order_id);
-        if let Some(order_side) = order {
+        if let Some((order_side, level)) = order {
             match order_side {
                 OrderSide::Buy => {
-                    self.bid_order = None;
+                    self.bid_orders[level] = None;
                 }
                 OrderSide::Sell => {
-                    self.ask_order = None;
+                    self.ask_orders[level] = None;
                 }
             }
         }
     }
     pub fn order_update(&mut self, fill: OrderUpdate) {
         let order = self.get_order_side_from_id(&fill.order_id);
-        if let Some(order_side) = order {
+        if let Some((order_side, level)) = order {
             match order_side {
                 OrderSide::Buy => {
-                    if let Some(order) = &mut self.bid_order {
+                    if let Some(order) = &mut self.bid_orders[level] {
                         order.matched += fill.amount;
                         self.exposure += fill.amount;
-                        if order.matched >= self.config.order_size {
-                            self.bid_order = None;
+                        if order.matched >= order.size {
+                            self.bid_orders[level] = None;
                         }
                     }
                 }
                 OrderSide::Sell => {
-                    if let Some(order) = &mut self.ask_order {
+                    if let Some(order) = &mut self.ask_orders[level] {
                         order.matched += fill.amount;
                         self.exposure -= fill.amount;
-                        if order.matched >= self.config.order_size {
-                            self.ask_order = None;
+                        if order.matched >= order.size {
+                            self.ask_orders[level] = None;
                         }
                     }
                 }
@@ -237,26 +811,67 @@ impl Market {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::infra::config::{
+        BlackoutWindow, DepthConfig, GtdConfig, ProtectionConfig, QuoteLevel, RequoteConfig,
+        SkewConfig, VolatilityConfig,
+    };
+    use crate::types::channel_types::BookLevel;
     fn mock_market(
         exposure: Option<Decimal>,
         open_bid: Option<OpenOrder>,
         open_ask: Option<OpenOrder>,
     ) -> Market {
         let config = MarketConfig {
-            slug: "test-slug".to_string(),
+            slug: Some("test-slug".to_string()),
+            market_slug: None,
+            condition_id: None,
+            token_ids: None,
+            primary_outcome: None,
+            group: None,
+            market: MarketSelector::Only,
             order_size: Decimal::from(5),
             spread: Decimal::new(2, 2),
             max_exposure: Decimal::from(5),
+            pricing_mode: PricingMode::BestPrice,
+            depth: DepthConfig::default(),
+            levels: Vec::new(),
+            requote: RequoteConfig::default(),
+            reward_max_spread: None,
+            skew: SkewConfig::default(),
+            volatility: None,
+            reward_min_size: None,
+            protection: None,
+            blackouts: Vec::new(),
+            stop_before_end_mins: None,
+            flatten_before_end: false,
+            gtd: None,
+            post_only: true,
         };
 
         Market {
+            slug: "test-market-slug".to_string(),
             token_ids: TokenIds {
                 buy_token: U256::from(1),
                 sell_token: U256::from(2),
             },
-            bid_order: open_bid,
-            ask_order: open_ask,
+            bid_orders: vec![open_bid],
+            ask_orders: vec![open_ask],
             exposure: exposure.unwrap_or(Decimal::from(0)),
+            tick_size: Decimal::new(1, 2),
+            min_order_size: Decimal::from(5),
+            price_history: PriceHistory::new(Duration::from_secs(60)),
+            order_book: OrderBook::new(),
+            protection: Protection::default(),
+            event_start: None,
+            end_date: None,
+            neg_risk: false,
+            retiring: false,
+            flatten_on_retire: false,
+            status: MarketStatus::Active,
+            last_trade_price: None,
+            group: None,
+            group_config: None,
+            group_exposure: Decimal::from(0),
             config,
         }
     }
@@ -264,6 +879,7 @@ mod tests {
     fn order_placed_update_updates_market_state() {
         let open_bid = Some(OpenOrder::default(
             Decimal::new(10, 2),
+            Decimal::from(5),
             OpenOrderStatus::Placed("first-bid-test-order-id".to_string()),
         ));
         let placed_bid_order = PlacedOrder {
@@ -280,13 +896,13 @@ mod tests {
         let bid_order_placed_result = market.order_placed_update(placed_bid_order);
         assert!(bid_order_placed_result.is_some());
         assert_eq!(
-            market.bid_order.as_ref().unwrap().status,
+            market.bid_orders[0].as_ref().unwrap().status,
             OpenOrderStatus::Placed("second-bid-test-order-id".to_string())
         );
 
         market.order_placed_update(placed_ask_order);
         assert_eq!(
-            market.ask_order.as_ref().unwrap().status,
+            market.ask_orders[0].as_ref().unwrap().status,
             OpenOrderStatus::Placed("ask-test-order-id".to_string())
         );
     }
@@ -295,21 +911,91 @@ mod tests {
         let market = mock_market(Some(Decimal::from(10)), None, None);
 
         let spreads = market.get_spreads();
-        assert_eq!(spreads.bid, market.config.spread);
-        assert_eq!(spreads.ask, Decimal::from(0));
+        assert_eq!(spreads.bid, market.config.spread * Decimal::from(2));
+        assert_eq!(spreads.ask, market.tick_size);
+    }
+
+    #[test]
+    fn inventory_skew_shifts_spreads_and_sizes_continuously() {
+        let mut market = mock_market(Some(Decimal::new(-25, 1)), None, None);
+        let spreads = market.get_spreads();
+        assert_eq!(spreads.bid, Decimal::new(1, 2));
+        assert_eq!(spreads.ask, Decimal::new(3, 2));
+
+        market.config.skew = SkewConfig {
+            shape: SkewShape::Quadratic,
+            max_skew: None,
+            max_size_skew: Decimal::new(5, 1),
+        };
+        let spreads = market.get_spreads();
+        assert_eq!(spreads.bid, Decimal::new(15, 3));
+        assert_eq!(spreads.ask, Decimal::new(25, 3));
+
+        let size_factors = market.get_size_factors();
+        assert_eq!(size_factors.bid, Decimal::from(1));
+        assert_eq!(size_factors.ask, Decimal::new(875, 3));
+
+        let new_prices = NewPrices {
+            best_bid: Decimal::new(50, 2),
+            best_ask: Decimal::new(52, 2),
+        };
+        let order_requests = market.price_update(new_prices).unwrap();
+        assert!(matches!(
+            order_requests.as_slice(),
+            [OrderRequest::PlaceOrder(order)] if order.token_id == U256::from(1)
+        ));
+        assert!(market.ask_orders[0].is_none());
+
+        market.config.skew = SkewConfig::default();
+        market.exposure = Decimal::from(-10);
+        let spreads = market.get_spreads();
+        assert_eq!(spreads.bid, Decimal::new(1, 2));
+        assert_eq!(spreads.ask, Decimal::new(4, 2));
+        assert_eq!(market.get_size_factors().ask, Decimal::from(1));
+    }
+
+    #[test]
+    fn midpoint_mode_quotes_around_midpoint_without_crossing() {
+        let mut market = mock_market(None, None, None);
+        market.config.pricing_mode = PricingMode::Midpoint;
+        let spreads = market.get_spreads();
+
+        let wide_book = NewPrices {
+            best_bid: Decimal::new(40, 2),
+            best_ask: Decimal::new(60, 2),
+        };
+        let quote_prices = market.get_quote_prices(&wide_book, &spreads);
+        assert_eq!(quote_prices.bid, Decimal::new(48, 2));
+        assert_eq!(quote_prices.ask, Decimal::new(52, 2));
+
+        let tight_book = NewPrices {
+            best_bid: Decimal::new(50, 2),
+            best_ask: Decimal::new(51, 2),
+        };
+        let zero_spreads = Spreads {
+            bid: Decimal::from(0),
+            ask: Decimal::from(0),
+        };
+        let quote_prices = market.get_quote_prices(&tight_book, &zero_spreads);
+        assert_eq!(quote_prices.bid, Decimal::new(50, 2));
+        assert_eq!(quote_prices.ask, Decimal::new(51, 2));
     }
 
     #[test]
     fn check_order_returns_none_if_open_order_pending() {
         let mut open_order = Some(OpenOrder::default(
             Decimal::new(11, 2),
+            Decimal::from(5),
             OpenOrderStatus::Pending,
         ));
+        let market = mock_market(None, None, None);
+        let requote_check = market.requote_check(Decimal::new(50, 2), Decimal::new(60, 2));
         let check_order_result = Market::check_order(
             &mut open_order,
             Decimal::new(10, 2),
             Decimal::from(5),
             U256::from(1),
+            &requote_check,
         );
         assert!(check_order_result.is_none());
     }
@@ -319,7 +1005,9 @@ mod tests {
         let open_bid = OpenOrder {
             price: Decimal::new(10, 2),
             status: OpenOrderStatus::Placed("a".to_string()),
+            size: Decimal::from(5),
             matched: Decimal::from(0),
+            updated_at: Instant::now(),
         };
 
         let mut market = mock_market(None, Some(open_bid), None);
@@ -328,7 +1016,10 @@ mod tests {
             amount: Decimal::from(3),
         };
         market.order_update(order_update);
-        assert_eq!(market.bid_order.as_ref().unwrap().matched, Decimal::from(3));
+        assert_eq!(
+            market.bid_orders[0].as_ref().unwrap().matched,
+            Decimal::from(3)
+        );
         assert_eq!(market.exposure, Decimal::from(3));
     }
     #[test]
@@ -336,7 +1027,9 @@ mod tests {
         let open_ask = OpenOrder {
             price: Decimal::new(10, 2),
             status: OpenOrderStatus::Placed("a".to_string()),
+            size: Decimal::from(5),
             matched: Decimal::from(1),
+            updated_at: Instant::now(),
         };
         let mut market = mock_market(None, None, Some(open_ask));
         let first_order_update = OrderUpdate {
@@ -348,8 +1041,826 @@ mod tests {
             amount: Decimal::from(1),
         };
         market.order_update(first_order_update);
-        assert_eq!(market.ask_order.as_ref().unwrap().matched, Decimal::from(4));
+        assert_eq!(
+            market.ask_orders[0].as_ref().unwrap().matched,
+            Decimal::from(4)
+        );
         market.order_update(second_order_update);
-        assert!(market.ask_order.is_none());
+        assert!(market.ask_orders[0].is_none());
+    }
+
+    #[test]
+    fn ladder_prices_are_floored_to_tick() {
+        let mut market = mock_market(None, None, None);
+        market.config.levels = vec![QuoteLevel {
+            offset: Decimal::new(15, 3),
+            size: Decimal::from(5),
+        }];
+
+        let new_prices = NewPrices {
+            best_bid: Decimal::new(40, 2),
+            best_ask: Decimal::new(45, 2),
+        };
+        market.price_update(new_prices).unwrap();
+        assert_eq!(
+            market.bid_orders[0].as_ref().unwrap().price,
+            Decimal::new(36, 2)
+        );
+        assert_eq!(
+            market.ask_orders[0].as_ref().unwrap().price,
+            Decimal::new(51, 2)
+        );
+    }
+
+    #[test]
+    fn levels_on_the_same_price_do_not_share_a_slot() {
+        let mut market = mock_market(None, None, None);
+        market.config.levels = vec![
+            QuoteLevel {
+                offset: Decimal::new(12, 3),
+                size: Decimal::from(5),
+            },
+            QuoteLevel {
+                offset: Decimal::new(18, 3),
+                size: Decimal::from(5),
+            },
+        ];
+        market.bid_orders = vec![None, None];
+        market.ask_orders = vec![None, None];
+
+        let new_prices = NewPrices {
+            best_bid: Decimal::new(40, 2),
+            best_ask: Decimal::new(45, 2),
+        };
+        let order_requests = market.price_update(new_prices).unwrap();
+        assert_eq!(order_requests.len(), 2);
+        assert_eq!(
+            market.bid_orders[0].as_ref().unwrap().price,
+            Decimal::new(36, 2)
+        );
+        assert!(market.bid_orders[1].is_none());
+
+        market.bid_orders[0] = Some(OpenOrder::default(
+            Decimal::new(36, 2),
+            Decimal::from(5),
+            OpenOrderStatus::Placed("level-0".to_string()),
+        ));
+        market.bid_orders[1] = Some(OpenOrder::default(
+            Decimal::new(36, 2),
+            Decimal::from(5),
+            OpenOrderStatus::Pending,
+        ));
+        let cancel_order_id = market.order_placed_update(PlacedOrder {
+            order_id: "level-1".to_string(),
+            price: Decimal::new(36, 2),
+            token_id: U256::from(1),
+        });
+        assert!(cancel_order_id.is_none());
+        assert_eq!(
+            market.bid_orders[0].as_ref().unwrap().status,
+            OpenOrderStatus::Placed("level-0".to_string())
+        );
+        assert_eq!(
+            market.bid_orders[1].as_ref().unwrap().status,
+            OpenOrderStatus::Placed("level-1".to_string())
+        );
+    }
+
+    #[test]
+    fn ladder_only_requotes_levels_that_move() {
+        let mut market = mock_market(None, None, None);
+        market.config.levels = vec![
+            QuoteLevel {
+                offset: Decimal::from(0),
+                size: Decimal::from(5),
+            },
+            QuoteLevel {
+                offset: Decimal::new(2, 2),
+                size: Decimal::from(10),
+            },
+        ];
+        market.bid_orders = vec![None, None];
+        market.ask_orders = vec![None, None];
+
+        let new_prices = NewPrices {
+            best_bid: Decimal::new(40, 2),
+            best_ask: Decimal::new(45, 2),
+        };
+        let order_requests = market.price_update(new_prices).unwrap();
+        assert_eq!(order_requests.len(), 4);
+        assert_eq!(
+            market.bid_orders[0].as_ref().unwrap().price,
+            Decimal::new(38, 2)
+        );
+        assert_eq!(
+            market.bid_orders[1].as_ref().unwrap().price,
+            Decimal::new(36, 2)
+        );
+        assert_eq!(
+            market.ask_orders[1].as_ref().unwrap().price,
+            Decimal::new(51, 2)
+        );
+        assert_eq!(
+            market.ask_orders[1].as_ref().unwrap().size,
+            Decimal::from(10)
+        );
+
+        market.bid_orders[0] = Some(OpenOrder::default(
+            Decimal::new(38, 2),
+            Decimal::from(5),
+            OpenOrderStatus::Placed("level-0".to_string()),
+        ));
+        market.bid_orders[1] = Some(OpenOrder::default(
+            Decimal::new(35, 2),
+            Decimal::from(10),
+            OpenOrderStatus::Placed("level-1".to_string()),
+        ));
+        market.ask_orders[0] = Some(OpenOrder::default(
+            Decimal::new(53, 2),
+            Decimal::from(5),
+            OpenOrderStatus::Placed("ask-level-0".to_string()),
+        ));
+        market.ask_orders[1] = Some(OpenOrder::default(
+            Decimal::new(51, 2),
+            Decimal::from(10),
+            OpenOrderStatus::Placed("ask-level-1".to_string()),
+        ));
+
+        let new_prices = NewPrices {
+            best_bid: Decimal::new(40, 2),
+            best_ask: Decimal::new(45, 2),
+        };
+        let order_requests = market.price_update(new_prices).unwrap();
+        assert_eq!(order_requests.len(), 2);
+        assert!(matches!(
+            &order_requests[0],
+            OrderRequest::CancelOrder(order_id) if order_id == "level-1"
+        ));
+    }
+
+    #[test]
+    fn requote_hysteresis_ignores_small_moves_unless_forced() {
+        let mut market = mock_market(None, None, None);
+        market.config.requote = RequoteConfig {
+            min_move_ticks: 2,
+            min_order_age_ms: 0,
+        };
+        market.config.reward_max_spread = Some(Decimal::new(3, 2));
+        let requote_check = market.requote_check(Decimal::new(50, 2), Decimal::new(55, 2));
+        let mut open_order = Some(OpenOrder::default(
+            Decimal::new(48, 2),
+            Decimal::from(5),
+            OpenOrderStatus::Placed("a".to_string()),
+        ));
+
+        let one_tick_move = Market::check_order(
+            &mut open_order,
+            Decimal::new(47, 2),
+            Decimal::from(5),
+            U256::from(1),
+            &requote_check,
+        );
+        assert!(one_tick_move.is_none());
+
+        let two_tick_move = Market::check_order(
+            &mut open_order,
+            Decimal::new(46, 2),
+            Decimal::from(5),
+            U256::from(1),
+            &requote_check,
+        );
+        assert!(two_tick_move.is_some());
+
+        let mut outside_band_order = Some(OpenOrder::default(
+            Decimal::new(46, 2),
+            Decimal::from(5),
+            OpenOrderStatus::Placed("b".to_string()),
+        ));
+        let requote_check = market.requote_check(Decimal::new(505, 3), Decimal::new(56, 2));
+        let forced_move = Market::check_order(
+            &mut outside_band_order,
+            Decimal::new(47, 2),
+            Decimal::from(5),
+            U256::from(1),
+            &requote_check,
+        );
+        assert!(forced_move.is_some());
+
+        let requote_check = market.requote_check(Decimal::new(50, 2), Decimal::new(49, 2));
+        assert!(!requote_check.must_requote(Decimal::new(48, 2)));
+        assert!(requote_check.must_requote(Decimal::new(49, 2)));
+    }
+
+    #[test]
+    fn requote_waits_for_min_order_age() {
+        let mut market = mock_market(None, None, None);
+        market.config.requote = RequoteConfig {
+            min_move_ticks: 1,
+            min_order_age_ms: 60_000,
+        };
+        let requote_check = market.requote_check(Decimal::new(50, 2), Decimal::new(55, 2));
+        let mut open_order = Some(OpenOrder::default(
+            Decimal::new(48, 2),
+            Decimal::from(5),
+            OpenOrderStatus::Placed("a".to_string()),
+        ));
+        let young_order_result = Market::check_order(
+            &mut open_order,
+            Decimal::new(46, 2),
+            Decimal::from(5),
+            U256::from(1),
+            &requote_check,
+        );
+        assert!(young_order_result.is_none());
+
+        open_order.as_mut().unwrap().updated_at = Instant::now() - Duration::from_secs(120);
+        let old_order_result = Market::check_order(
+            &mut open_order,
+            Decimal::new(46, 2),
+            Decimal::from(5),
+            U256::from(1),
+            &requote_check,
+        );
+        assert!(old_order_result.is_some());
+    }
+
+    #[test]
+    fn volatility_widens_spread_within_bounds() {
+        let mut market = mock_market(None, None, None);
+        market.config.volatility = Some(VolatilityConfig {
+            window_secs: 60,
+            min_spread: Decimal::new(1, 2),
+            max_spread: Decimal::new(5, 2),
+            multiplier: Decimal::from(1),
+        });
+        let start = Instant::now();
+        market.price_history.push(start, Decimal::new(50, 2));
+        assert_eq!(market.get_spreads().bid, Decimal::new(1, 2));
+
+        market
+            .price_history
+            .push(start + Duration::from_secs(1), Decimal::new(52, 2));
+        assert_eq!(market.get_spreads().bid, Decimal::new(3, 2));
+
+        market
+            .price_history
+            .push(start + Duration::from_secs(2), Decimal::new(40, 2));
+        assert_eq!(market.get_spreads().bid, Decimal::new(5, 2));
+
+        market
+            .price_history
+            .push(start + Duration::from_secs(120), Decimal::new(40, 2));
+        assert_eq!(market.get_spreads().bid, Decimal::new(1, 2));
+    }
+
+    #[test]
+    fn extreme_midpoint_pulls_quotes_without_scoring_pair() {
+        let open_ask = OpenOrder::default(
+            Decimal::new(94, 2),
+            Decimal::from(5),
+            OpenOrderStatus::Placed("ask".to_string()),
+        );
+        let mut market = mock_market(None, None, Some(open_ask));
+        let new_prices = NewPrices {
+            best_bid: Decimal::new(2, 2),
+            best_ask: Decimal::new(4, 2),
+        };
+        let order_requests = market.price_update(new_prices).unwrap();
+        assert_eq!(order_requests.len(), 1);
+        assert!(matches!(
+            &order_requests[0],
+            OrderRequest::CancelOrder(order_id) if order_id == "ask"
+        ));
+        assert!(market.bid_orders[0].is_none());
+        assert!(market.ask_orders[0].is_none());
+
+        let new_prices = NewPrices {
+            best_bid: Decimal::new(4, 2),
+            best_ask: Decimal::new(6, 2),
+        };
+        let order_requests = market.price_update(new_prices).unwrap();
+        assert_eq!(order_requests.len(), 2);
+    }
+
+    #[test]
+    fn order_book_tracks_changes_and_detects_inconsistency() {
+        let mut market = mock_market(None, None, None);
+        assert!(market.order_book.needs_snapshot());
+        assert!(!market.order_book.needs_snapshot());
+        let snapshot = BookSnapshot {
+            bids: vec![
+                BookLevel {
+                    price: Decimal::new(40, 2),
+                    size: Decimal::from(100),
+                },
+                BookLevel {
+                    price: Decimal::new(39, 2),
+                    size: Decimal::from(50),
+                },
+            ],
+            asks: vec![BookLevel {
+                price: Decimal::new(45, 2),
+                size: Decimal::from(100),
+            }],
+        };
+        let order_requests = market.book_snapshot(snapshot);
+        assert!(order_requests.is_some());
+        assert_eq!(
+            market.bid_orders[0].as_ref().unwrap().price,
+            Decimal::new(38, 2)
+        );
+
+        let bid_removed = BookChange {
+            side: OrderSide::Buy,
+            price: Decimal::new(40, 2),
+            size: Decimal::from(0),
+            best_bid: Some(Decimal::new(39, 2)),
+            best_ask: Some(Decimal::new(45, 2)),
+        };
+        market.book_change(bid_removed);
+        assert_eq!(market.order_book.best_bid(), Some(Decimal::new(39, 2)));
+        assert!(!market.order_book.needs_snapshot());
+        let bid_price = market.bid_orders[0].as_ref().unwrap().price;
+        market.order_placed_update(PlacedOrder {
+            order_id: "bid-order-id".to_string(),
+            price: bid_price,
+            token_id: U256::from(1),
+        });
+
+        let missed_update = BookChange {
+            side: OrderSide::Sell,
+            price: Decimal::new(46, 2),
+            size: Decimal::from(10),
+            best_bid: Some(Decimal::new(39, 2)),
+            best_ask: Some(Decimal::new(44, 2)),
+        };
+        let order_requests = market.book_change(missed_update).unwrap();
+        assert!(matches!(
+            order_requests.as_slice(),
+            [OrderRequest::CancelOrder(order_id)] if order_id == "bid-order-id"
+        ));
+        assert!(market.order_book.is_stale());
+        assert!(market.order_book.needs_snapshot());
+        assert!(!market.order_book.needs_snapshot());
+
+        market.order_book.snapshot_failed();
+        assert!(market.order_book.needs_snapshot());
+    }
+
+    #[test]
+    fn depth_mode_hides_behind_liquidity_inside_reward_band() {
+        let mut market = mock_market(None, None, None);
+        market.config.pricing_mode = PricingMode::Depth;
+        market.config.spread = Decimal::from(0);
+        market.config.depth = DepthConfig {
+            size_ahead: Some(Decimal::from(250)),
+            level: None,
+        };
+        let level = |price, size| BookLevel {
+            price: Decimal::new(price, 2),
+            size: Decimal::from(size),
+        };
+        market.order_book.apply_snapshot(BookSnapshot {
+            bids: vec![level(40, 100), level(39, 200), level(38, 500)],
+            asks: vec![level(45, 100), level(46, 300)],
+        });
+        let new_prices = market.order_book.new_prices().unwrap();
+        let spreads = market.get_spreads();
+
+        let quote_prices = market.get_quote_prices(&new_prices, &spreads);
+        assert_eq!(quote_prices.bid, Decimal::new(39, 2));
+        assert_eq!(quote_prices.ask, Decimal::new(46, 2));
+
+        market.config.depth = DepthConfig {
+            size_ahead: None,
+            level: Some(3),
+        };
+        let quote_prices = market.get_quote_prices(&new_prices, &spreads);
+        assert_eq!(quote_prices.bid, Decimal::new(38, 2));
+        assert_eq!(quote_prices.ask, Decimal::new(46, 2));
+
+        market.config.reward_max_spread = Some(Decimal::new(3, 2));
+        let quote_prices = market.get_quote_prices(&new_prices, &spreads);
+        assert_eq!(quote_prices.bid, Decimal::new(40, 2));
+        assert_eq!(quote_prices.ask, Decimal::new(45, 2));
+    }
+
+    #[test]
+    fn protection_pulls_quotes_on_mid_jump_and_cools_down() {
+        let open_bid = OpenOrder::default(
+            Decimal::new(48, 2),
+            Decimal::from(5),
+            OpenOrderStatus::Placed("bid".to_string()),
+        );
+        let mut market = mock_market(None, Some(open_bid), None);
+        market.config.protection = Some(ProtectionConfig {
+            mid_jump: Some(Decimal::new(5, 2)),
+            ..ProtectionConfig::default()
+        });
+        let prices = |best_bid, best_ask| NewPrices {
+            best_bid: Decimal::new(best_bid, 2),
+            best_ask: Decimal::new(best_ask, 2),
+        };
+        assert!(market.price_update(prices(50, 52)).is_some());
+        market.bid_orders[0] = Some(OpenOrder::default(
+            Decimal::new(48, 2),
+            Decimal::from(5),
+            OpenOrderStatus::Placed("bid".to_string()),
+        ));
+
+        let order_requests = market.price_update(prices(40, 42)).unwrap();
+        assert_eq!(order_requests.len(), 1);
+        assert!(matches!(
+            &order_requests[0],
+            OrderRequest::CancelOrder(order_id) if order_id == "bid"
+        ));
+        assert!(market.price_update(prices(40, 42)).is_none());
+        assert!(market.bid_orders[0].is_none());
+    }
+
+    #[test]
+    fn protection_detects_sweeps_through_several_levels() {
+        let mut market = mock_market(None, None, None);
+        market.config.protection = Some(ProtectionConfig {
+            sweep_levels: Some(2),
+            ..ProtectionConfig::default()
+        });
+        let level = |price, size| BookLevel {
+            price: Decimal::new(price, 2),
+            size: Decimal::from(size),
+        };
+        market.book_snapshot(BookSnapshot {
+            bids: vec![level(40, 100)],
+            asks: vec![level(45, 10), level(46, 10), level(47, 10)],
+        });
+        let clear_ask = |price, best_ask| BookChange {
+            side: OrderSide::Sell,
+            price: Decimal::new(price, 2),
+            size: Decimal::from(0),
+            best_bid: Some(Decimal::new(40, 2)),
+            best_ask: Some(Decimal::new(best_ask, 2)),
+        };
+        market.bid_orders[0] = Some(OpenOrder::default(
+            Decimal::new(38, 2),
+            Decimal::from(5),
+            OpenOrderStatus::Placed("bid".to_string()),
+        ));
+        assert!(market.book_change(clear_ask(45, 46)).is_none());
+
+        let order_requests = market.book_change(clear_ask(46, 47)).unwrap();
+        assert_eq!(order_requests.len(), 1);
+        assert!(matches!(
+            &order_requests[0],
+            OrderRequest::CancelOrder(order_id) if order_id == "bid"
+        ));
+    }
+
+    #[test]
+    fn cleared_levels_are_not_recorded_without_protection() {
+        let mut market = mock_market(None, None, None);
+        let level = |price, size| BookLevel {
+            price: Decimal::new(price, 2),
+            size: Decimal::from(size),
+        };
+        market.book_snapshot(BookSnapshot {
+            bids: vec![level(40, 100)],
+            asks: vec![level(45, 10), level(46, 10), level(47, 10)],
+        });
+        let ask_change = |price, size, best_ask| BookChange {
+            side: OrderSide::Sell,
+            price: Decimal::new(price, 2),
+            size: Decimal::from(size),
+            best_bid: Some(Decimal::new(40, 2)),
+            best_ask: Some(Decimal::new(best_ask, 2)),
+        };
+        market.book_change(ask_change(45, 0, 46));
+        market.book_change(ask_change(46, 0, 47));
+
+        market.config.protection = Some(ProtectionConfig {
+            sweep_levels: Some(2),
+            ..ProtectionConfig::default()
+        });
+        market.bid_orders[0] = Some(OpenOrder::default(
+            Decimal::new(38, 2),
+            Decimal::from(5),
+            OpenOrderStatus::Placed("bid".to_string()),
+        ));
+        assert!(market.book_change(ask_change(47, 20, 47)).is_none());
+    }
+
+    #[test]
+    fn blackout_windows_pull_or_widen_quotes() {
+        let open_bid = OpenOrder::default(
+            Decimal::new(48, 2),
+            Decimal::from(5),
+            OpenOrderStatus::Placed("bid".to_string()),
+        );
+        let mut market = mock_market(None, Some(open_bid), None);
+        market.event_start = Some(Utc::now() + chrono::TimeDelta::minutes(5));
+        market.config.blackouts = vec![BlackoutWindow {
+            start: None,
+            end: None,
+            before_event_start_mins: Some(10),
+            after_event_start_mins: Some(120),
+            spread_multiplier: None,
+        }];
+        let new_prices = NewPrices {
+            best_bid: Decimal::new(50, 2),
+            best_ask: Decimal::new(52, 2),
+        };
+        let order_requests = market.price_update(new_prices).unwrap();
+        assert_eq!(order_requests.len(), 1);
+        assert!(market.bid_orders[0].is_none());
+
+        market.config.blackouts[0].spread_multiplier = Some(Decimal::from(2));
+        assert_eq!(market.get_spreads().bid, Decimal::new(4, 2));
+
+        market.event_start = Some(Utc::now() + chrono::TimeDelta::minutes(30));
+        assert_eq!(market.get_spreads().bid, Decimal::new(2, 2));
+    }
+
+    #[test]
+    fn quoting_stops_and_flattens_before_end_date() {
+        let open_bid = OpenOrder::default(
+            Decimal::new(48, 2),
+            Decimal::from(5),
+            OpenOrderStatus::Placed("bid".to_string()),
+        );
+        let mut market = mock_market(Some(Decimal::from(3)), Some(open_bid), None);
+        market.end_date = Some(Utc::now() + TimeDelta::minutes(30));
+        market.config.stop_before_end_mins = Some(60);
+        let new_prices = || NewPrices {
+            best_bid: Decimal::new(50, 2),
+            best_ask: Decimal::new(52, 2),
+        };
+
+        market.config.flatten_before_end = true;
+        let order_requests = market.price_update(new_prices()).unwrap();
+        assert_eq!(order_requests.len(), 2);
+        assert!(matches!(
+            &order_requests[0],
+            OrderRequest::CancelOrder(order_id) if order_id == "bid"
+        ));
+        let flatten_order = market.ask_orders[0].as_ref().unwrap();
+        assert_eq!(flatten_order.price, Decimal::new(50, 2));
+        assert_eq!(flatten_order.size, Decimal::from(3));
+
+        market.config.flatten_before_end = false;
+        market.ask_orders[0] = Some(OpenOrder::default(
+            Decimal::new(50, 2),
+            Decimal::from(3),
+            OpenOrderStatus::Placed("flatten".to_string()),
+        ));
+        let order_requests = market.price_update(new_prices()).unwrap();
+        assert_eq!(order_requests.len(), 1);
+        assert!(market.ask_orders[0].is_none());
+    }
+
+    #[test]
+    fn gtd_orders_get_expiration_and_refresh_before_expiry() {
+        let mut market = mock_market(None, None, None);
+        market.config.gtd = Some(GtdConfig {
+            expiration_secs: 300,
+            refresh_before_secs: 60,
+        });
+        let new_prices = || NewPrices {
+            best_bid: Decimal::new(50, 2),
+            best_ask: Decimal::new(52, 2),
+        };
+        let order_requests = market.price_update(new_prices()).unwrap();
+        let expiration_floor = Utc::now() + TimeDelta::seconds(300);
+        assert!(order_requests.iter().all(|order_request| matches!(
+            order_request,
+            OrderRequest::PlaceOrder(order)
+                if order.expiration.is_some_and(|expiration| expiration > expiration_floor)
+        )));
+
+        let placed_bid = |age_secs| {
+            let mut open_order = OpenOrder::default(
+                Decimal::new(48, 2),
+                Decimal::from(5),
+                OpenOrderStatus::Placed("bid".to_string()),
+            );
+            open_order.updated_at = Instant::now() - Duration::from_secs(age_secs);
+            Some(open_order)
+        };
+        market.ask_orders[0] = None;
+        market.bid_orders[0] = placed_bid(100);
+        let order_requests = market.price_update(new_prices()).unwrap();
+        assert_eq!(order_requests.len(), 1);
+
+        market.ask_orders[0] = None;
+        market.bid_orders[0] = placed_bid(250);
+        let order_requests = market.price_update(new_prices()).unwrap();
+        assert_eq!(order_requests.len(), 3);
+    }
+
+    #[test]
+    fn quotes_never_cross_and_are_post_only() {
+        let mut market = mock_market(None, None, None);
+        market.neg_risk = true;
+        market.config.levels = vec![QuoteLevel {
+            offset: Decimal::new(-5, 2),
+            size: Decimal::from(5),
+        }];
+        let new_prices = NewPrices {
+            best_bid: Decimal::new(50, 2),
+            best_ask: Decimal::new(52, 2),
+        };
+        let order_requests = market.price_update(new_prices).unwrap();
+        assert_eq!(
+            market.bid_orders[0].as_ref().unwrap().price,
+            Decimal::new(51, 2)
+        );
+        assert_eq!(
+            market.ask_orders[0].as_ref().unwrap().price,
+            Decimal::new(49, 2)
+        );
+        assert!(order_requests.iter().all(|order_request| matches!(
+            order_request,
+            OrderRequest::PlaceOrder(order) if order.post_only && order.neg_risk
+        )));
+
+        market.order_rejected(RejectedOrder {
+            price: Decimal::new(51, 2),
+            token_id: U256::from(1),
+        });
+        assert!(market.bid_orders[0].is_none());
+        assert!(market.ask_orders[0].is_some());
+    }
+
+    #[test]
+    fn retired_market_cancels_quotes_and_drains() {
+        let open_bid = Some(OpenOrder::default(
+            Decimal::new(48, 2),
+            Decimal::from(5),
+            OpenOrderStatus::Placed("bid-order-id".to_string()),
+        ));
+        let open_ask = Some(OpenOrder::default(
+            Decimal::new(48, 2),
+            Decimal::from(5),
+            OpenOrderStatus::Pending,
+        ));
+        let mut market = mock_market(None, open_bid, open_ask);
+        let order_requests = market.retire(false).unwrap();
+        assert!(matches!(
+            order_requests.as_slice(),
+            [OrderRequest::CancelOrder(order_id)] if order_id == "bid-order-id"
+        ));
+        assert!(!market.is_drained());
+
+        market.order_placed_update(PlacedOrder {
+            order_id: "ask-order-id".to_string(),
+            price: Decimal::new(48, 2),
+            token_id: U256::from(2),
+        });
+        let new_prices = NewPrices {
+            best_bid: Decimal::new(50, 2),
+            best_ask: Decimal::new(52, 2),
+        };
+        let order_requests = market.price_update(new_prices).unwrap();
+        assert!(matches!(
+            order_requests.as_slice(),
+            [OrderRequest::CancelOrder(order_id)] if order_id == "ask-order-id"
+        ));
+        assert!(market.is_drained());
+
+        market.resize(Decimal::from(10), Decimal::from(10));
+        assert!(!market.retiring);
+        assert!(!market.is_drained());
+    }
+
+    #[test]
+    fn reconfigured_market_keeps_orders_and_flattens_before_draining() {
+        let open_bid = Some(OpenOrder::default(
+            Decimal::new(48, 2),
+            Decimal::from(5),
+            OpenOrderStatus::Placed("bid-order-id".to_string()),
+        ));
+        let mut market = mock_market(Some(Decimal::from(3)), open_bid, None);
+        let mut reloaded_market = mock_market(None, None, None);
+        reloaded_market.config.spread = Decimal::new(3, 2);
+        reloaded_market.config.levels = vec![
+            QuoteLevel {
+                offset: Decimal::from(0),
+                size: Decimal::from(5),
+            },
+            QuoteLevel {
+                offset: Decimal::new(1, 2),
+                size: Decimal::from(5),
+            },
+        ];
+        reloaded_market.tick_size = Decimal::new(1, 3);
+        reloaded_market.end_date = Some(Utc::now() + TimeDelta::days(1));
+        assert!(market.reconfigure(reloaded_market).unwrap().is_none());
+        assert_eq!(market.tick_size, Decimal::new(1, 3));
+        assert!(market.end_date.is_some());
+        assert_eq!(market.bid_orders.len(), 2);
+        assert_eq!(market.ask_orders.len(), 2);
+        assert!(market.bid_orders[0].is_some());
+        assert_eq!(market.exposure, Decimal::from(3));
+        assert_eq!(market.config.spread, Decimal::new(3, 2));
+
+        let mut flipped_market = mock_market(None, None, None);
+        flipped_market.token_ids = TokenIds {
+            buy_token: U256::from(2),
+            sell_token: U256::from(1),
+        };
+        flipped_market.config.spread = Decimal::new(5, 2);
+        assert!(market.reconfigure(flipped_market).is_err());
+        assert_eq!(market.config.spread, Decimal::new(3, 2));
+
+        let order_requests = market.retire(true).unwrap();
+        assert!(matches!(
+            order_requests.as_slice(),
+            [OrderRequest::CancelOrder(order_id)] if order_id == "bid-order-id"
+        ));
+        assert!(!market.is_drained());
+
+        let new_prices = NewPrices {
+            best_bid: Decimal::new(50, 2),
+            best_ask: Decimal::new(52, 2),
+        };
+        let order_requests = market.price_update(new_prices).unwrap();
+        let [OrderRequest::PlaceOrder(flatten_order)] = order_requests.as_slice() else {
+            panic!("expected a single flatten order, got {order_requests:?}");
+        };
+        assert_eq!(flatten_order.token_id, U256::from(2));
+        assert_eq!(flatten_order.size, Decimal::from(3));
+        assert!(!flatten_order.post_only);
+
+        market.order_placed_update(PlacedOrder {
+            order_id: "flatten-order-id".to_string(),
+            price: flatten_order.price,
+            token_id: flatten_order.token_id,
+        });
+        market.order_update(OrderUpdate {
+            order_id: "flatten-order-id".to_string(),
+            amount: Decimal::from(3),
+        });
+        assert_eq!(market.exposure, Decimal::from(0));
+        assert!(market.is_drained());
+    }
+
+    #[test]
+    fn lifecycle_events_pause_and_stop_quoting() {
+        let open_bid = Some(OpenOrder::default(
+            Decimal::new(48, 2),
+            Decimal::from(5),
+            OpenOrderStatus::Placed("bid-order-id".to_string()),
+        ));
+        let mut market = mock_market(None, open_bid, None);
+        let new_prices = || NewPrices {
+            best_bid: Decimal::new(50, 2),
+            best_ask: Decimal::new(52, 2),
+        };
+
+        let order_requests = market
+            .lifecycle_update(LifecycleEvent::TradingPaused)
+            .unwrap();
+        assert!(matches!(
+            order_requests.as_slice(),
+            [OrderRequest::CancelOrder(order_id)] if order_id == "bid-order-id"
+        ));
+        assert!(market.price_update(new_prices()).is_none());
+        assert!(!market.is_drained());
+
+        market.lifecycle_update(LifecycleEvent::TradingResumed);
+        market.lifecycle_update(LifecycleEvent::TickSizeChanged(Decimal::new(1, 3)));
+        assert_eq!(market.tick_size, Decimal::new(1, 3));
+        assert!(market.price_update(new_prices()).is_some());
+
+        market.bid_orders[0] = None;
+        market.ask_orders[0] = None;
+        market.lifecycle_update(LifecycleEvent::Resolved);
+        assert_eq!(market.status, MarketStatus::Stopped);
+        assert!(market.is_drained());
+        assert!(market.price_update(new_prices()).is_none());
+    }
+
+    #[test]
+    fn group_exposure_tightens_quoting_across_members() {
+        let mut market = mock_market(None, None, None);
+        let spreads = market.get_spreads();
+        assert_eq!(spreads.bid, spreads.ask);
+
+        market.group_config = Some(GroupConfig {
+            name: "event".to_string(),
+            max_exposure: Decimal::from(20),
+            max_skew: Some(Decimal::new(2, 2)),
+            max_size_skew: Decimal::new(5, 1),
+        });
+        market.group_exposure = Decimal::from(10);
+        let spreads = market.get_spreads();
+        assert_eq!(spreads.bid, Decimal::new(3, 2));
+        assert_eq!(spreads.ask, Decimal::new(1, 2));
+        let size_factors = market.get_size_factors();
+        assert_eq!(size_factors.bid, Decimal::new(75, 2));
+        assert_eq!(size_factors.ask, Decimal::from(1));
+
+        market.exposure = Decimal::from(5);
+        let spreads = market.get_spreads();
+        assert_eq!(spreads.bid, Decimal::new(5, 2));
+        assert_eq!(spreads.ask, Decimal::new(1, 2));
     }
 }
@@ -1,11 +1,16 @@
 use alloy::primitives::U256;
+use chrono::{DateTime, Utc};
 use polymarket_client_sdk::types::Decimal;
+use std::time::{Duration, Instant};
 
 #[derive(Debug)]
 pub struct Order {
     pub price: Decimal,
     pub size: Decimal,
     pub token_id: U256,
+    pub expiration: Option<DateTime<Utc>>,
+    pub post_only: bool,
+    pub neg_risk: bool,
 }
 
 impl Order {
@@ -14,6 +19,9 @@ impl Order {
             price,
             size,
             token_id,
+            expiration: None,
+            post_only: true,
+            neg_risk: false,
         }
     }
 }
@@ -23,16 +31,26 @@ pub enum OrderRequest {
     CancelOrder(String),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MarketStatus {
+    Active,
+    Paused,
+    Stopped,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum OpenOrderStatus {
     Pending,
     Placed(String),
 }
 
+#[derive(Debug)]
 pub struct OpenOrder {
     pub price: Decimal,
+    pub size: Decimal,
     pub status: OpenOrderStatus,
     pub matched: Decimal,
+    pub updated_at: Instant,
 }
 
 pub struct Spreads {
@@ -40,16 +58,87 @@ pub struct Spreads {
     pub ask: Decimal,
 }
 
+pub struct SizeFactors {
+    pub bid: Decimal,
+    pub ask: Decimal,
+}
+
 impl OpenOrder {
-    pub fn default(price: Decimal, status: OpenOrderStatus) -> Self {
+    pub fn default(price: Decimal, size: Decimal, status: OpenOrderStatus) -> Self {
         Self {
             price,
+            size,
             status,
             matched: Decimal::from(0),
+            updated_at: Instant::now(),
+        }
+    }
+}
+
+pub struct RequoteCheck {
+    pub min_move: Decimal,
+    pub min_age: Duration,
+    pub midpoint: Decimal,
+    pub cross_price: Decimal,
+    pub max_spread: Option<Decimal>,
+    pub refresh_after: Option<Duration>,
+}
+
+impl RequoteCheck {
+    pub fn must_requote(&self, price: Decimal) -> bool {
+        let crossing_risk = price >= self.cross_price;
+        let outside_reward_band = self
+            .max_spread
+            .is_some_and(|max_spread| self.midpoint - price > max_spread);
+        crossing_risk || outside_reward_band
+    }
+
+    pub fn scores(&self, order: &Order, min_size: Option<Decimal>) -> bool {
+        let in_price_range =
+            order.price >= Decimal::new(1, 2) && order.price <= Decimal::new(99, 2);
+        let in_reward_band = self
+            .max_spread
+            .is_none_or(|max_spread| self.midpoint - order.price <= max_spread);
+        let large_enough =
+            order.size > Decimal::from(0) && min_size.is_none_or(|min_size| order.size >= min_size);
+        in_price_range && in_reward_band && large_enough
+    }
+
+    pub fn should_requote(&self, open_order: &OpenOrder, price: Decimal, size: Decimal) -> bool {
+        if self
+            .refresh_after
+            .is_some_and(|refresh_after| open_order.updated_at.elapsed() >= refresh_after)
+        {
+            return true;
         }
+        if open_order.price == price && open_order.size == size {
+            return false;
+        }
+        if self.must_requote(open_order.price) {
+            return true;
+        }
+        let moved_enough =
+            (price - open_order.price).abs() >= self.min_move || open_order.size != size;
+        moved_enough && open_order.updated_at.elapsed() >= self.min_age
     }
 }
 pub struct NewPrices {
     pub best_bid: Decimal,
     pub best_ask: Decimal,
 }
+
+impl NewPrices {
+    pub fn midpoint(&self) -> Decimal {
+        (self.best_bid + self.best_ask) / Decimal::from(2)
+    }
+
+    pub fn requires_two_sided(&self) -> bool {
+        let midpoint = self.midpoint();
+        midpoint < Decimal::new(10, 2) || midpoint > Decimal::new(90, 2)
+    }
+}
+
+pub struct QuotePrices {
+    pub bid: Decimal,
+    pub ask: Decimal,
+}
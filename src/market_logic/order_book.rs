@@ -0,0 +1,138 @@
+use crate::market_logic::market_types::NewPrices;
+use crate::types::channel_types::{BookChange, BookSnapshot, OrderSide};
+use polymarket_client_sdk::types::Decimal;
+use std::collections::BTreeMap;
+
+#[derive(Debug)]
+pub struct OrderBook {
+    bids: BTreeMap<Decimal, Decimal>,
+    asks: BTreeMap<Decimal, Decimal>,
+    stale: bool,
+    snapshot_requested: bool,
+}
+
+impl Default for OrderBook {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrderBook {
+    pub fn new() -> Self {
+        Self {
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            stale: true,
+            snapshot_requested: false,
+        }
+    }
+
+    pub fn apply_snapshot(&mut self, snapshot: BookSnapshot) {
+        self.bids = snapshot
+            .bids
+            .into_iter()
+            .filter(|level| level.size > Decimal::from(0))
+            .map(|level| (level.price, level.size))
+            .collect();
+        self.asks = snapshot
+            .asks
+            .into_iter()
+            .filter(|level| level.size > Decimal::from(0))
+            .map(|level| (level.price, level.size))
+            .collect();
+        self.stale = self.is_crossed();
+        self.snapshot_requested = false;
+    }
+
+    pub fn clears_best_level(&self, change: &BookChange) -> bool {
+        let best_price = match change.side {
+            OrderSide::Buy => self.best_bid(),
+            OrderSide::Sell => self.best_ask(),
+        };
+        !self.stale && change.size == Decimal::from(0) && best_price == Some(change.price)
+    }
+
+    pub fn apply_change(&mut self, change: BookChange) {
+        if self.stale {
+            return;
+        }
+        let levels = match change.side {
+            OrderSide::Buy => &mut self.bids,
+            OrderSide::Sell => &mut self.asks,
+        };
+        if change.size > Decimal::from(0) {
+            levels.insert(change.price, change.size);
+        } else {
+            levels.remove(&change.price);
+        }
+
+        let best_bid_matches = change
+            .best_bid
+            .is_none_or(|best_bid| self.best_bid().unwrap_or(Decimal::from(0)) == best_bid);
+        let best_ask_matches = change
+            .best_ask
+            .is_none_or(|best_ask| self.best_ask().unwrap_or(Decimal::from(1)) == best_ask);
+        self.stale = self.is_crossed() || !best_bid_matches || !best_ask_matches;
+    }
+
+    pub fn needs_snapshot(&mut self) -> bool {
+        if self.stale && !self.snapshot_requested {
+            self.snapshot_requested = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn snapshot_failed(&mut self) {
+        self.snapshot_requested = false;
+    }
+
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    pub fn best_bid(&self) -> Option<Decimal> {
+        self.bids.keys().next_back().copied()
+    }
+
+    pub fn best_ask(&self) -> Option<Decimal> {
+        self.asks.keys().next().copied()
+    }
+
+    pub fn bids(&self) -> impl Iterator<Item = (Decimal, Decimal)> + '_ {
+        self.bids.iter().rev().map(|(price, size)| (*price, *size))
+    }
+
+    pub fn asks(&self) -> impl Iterator<Item = (Decimal, Decimal)> + '_ {
+        self.asks.iter().map(|(price, size)| (*price, *size))
+    }
+
+    pub fn imbalance(&self, levels: usize) -> Option<Decimal> {
+        let bid_size: Decimal = self.bids().take(levels).map(|(_, size)| size).sum();
+        let ask_size: Decimal = self.asks().take(levels).map(|(_, size)| size).sum();
+        let total_size = bid_size + ask_size;
+        if total_size > Decimal::from(0) {
+            Some((bid_size - ask_size) / total_size)
+        } else {
+            None
+        }
+    }
+
+    pub fn new_prices(&self) -> Option<NewPrices> {
+        if self.stale {
+            return None;
+        }
+        Some(NewPrices {
+            best_bid: self.best_bid()?,
+            best_ask: self.best_ask()?,
+        })
+    }
+
+    fn is_crossed(&self) -> bool {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(best_bid), Some(best_ask)) => best_bid >= best_ask,
+            _ => false,
+        }
+    }
+}
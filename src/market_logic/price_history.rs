@@ -0,0 +1,44 @@
+use polymarket_client_sdk::types::Decimal;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct PriceHistory {
+    window: Duration,
+    midpoints: VecDeque<(Instant, Decimal)>,
+}
+
+impl PriceHistory {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            midpoints: VecDeque::new(),
+        }
+    }
+
+    pub fn set_window(&mut self, window: Duration) {
+        self.window = window;
+    }
+
+    pub fn push(&mut self, now: Instant, midpoint: Decimal) {
+        self.midpoints.push_back((now, midpoint));
+        while let Some((timestamp, _)) = self.midpoints.front() {
+            if now.duration_since(*timestamp) > self.window {
+                self.midpoints.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    pub fn price_range(&self) -> Decimal {
+        let mut prices = self.midpoints.iter().map(|(_, midpoint)| *midpoint);
+        let Some(first) = prices.next() else {
+            return Decimal::from(0);
+        };
+        let (low, high) = prices.fold((first, first), |(low, high), price| {
+            (low.min(price), high.max(price))
+        });
+        high - low
+    }
+}
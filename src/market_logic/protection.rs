@@ -0,0 +1,62 @@
+use crate::infra::config::ProtectionConfig;
+use crate::market_logic::order_book::OrderBook;
+use polymarket_client_sdk::types::Decimal;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Default)]
+pub struct Protection {
+    cooldown_until: Option<Instant>,
+    last_midpoint: Option<Decimal>,
+    cleared_levels: VecDeque<Instant>,
+}
+
+impl Protection {
+    pub fn record_level_cleared(&mut self, config: &ProtectionConfig, now: Instant) {
+        self.prune_cleared_levels(config, now);
+        self.cleared_levels.push_back(now);
+    }
+
+    fn prune_cleared_levels(&mut self, config: &ProtectionConfig, now: Instant) {
+        let sweep_window = Duration::from_millis(config.sweep_window_ms);
+        while let Some(cleared_at) = self.cleared_levels.front() {
+            if now.duration_since(*cleared_at) > sweep_window {
+                self.cleared_levels.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    pub fn is_triggered(
+        &mut self,
+        config: &ProtectionConfig,
+        now: Instant,
+        midpoint: Decimal,
+        order_book: &OrderBook,
+    ) -> bool {
+        let mid_jumped = self
+            .last_midpoint
+            .zip(config.mid_jump)
+            .is_some_and(|(last_midpoint, mid_jump)| (midpoint - last_midpoint).abs() >= mid_jump);
+        self.last_midpoint = Some(midpoint);
+
+        self.prune_cleared_levels(config, now);
+        let swept = config
+            .sweep_levels
+            .is_some_and(|sweep_levels| self.cleared_levels.len() >= sweep_levels);
+
+        let imbalanced = config.imbalance_threshold.is_some_and(|threshold| {
+            order_book
+                .imbalance(config.imbalance_levels)
+                .is_some_and(|imbalance| imbalance.abs() >= threshold)
+        });
+
+        if mid_jumped || swept || imbalanced {
+            self.cooldown_until = Some(now + Duration::from_secs(config.cooldown_secs));
+            self.cleared_levels.clear();
+        }
+        self.cooldown_until
+            .is_some_and(|cooldown_until| now < cooldown_until)
+    }
+}
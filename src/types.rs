@@ -1,4 +1,6 @@
 pub mod channel_types;
+pub mod market_info;
 pub mod token_ids;
 
+pub use market_info::MarketInfo;
 pub use token_ids::TokenIds;
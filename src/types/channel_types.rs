@@ -1,3 +1,5 @@
+use crate::infra::config::GroupConfig;
+use crate::market_logic::Market;
 use alloy::primitives::{B256, U256};
 use polymarket_client_sdk::types::Decimal;
 
@@ -27,9 +29,36 @@ pub struct PlacedOrder {
 }
 
 #[derive(Debug)]
-pub struct MarketData {
-    pub best_bid: Decimal,
-    pub best_ask: Decimal,
+pub struct RejectedOrder {
+    pub price: Decimal,
+    pub token_id: U256,
+}
+
+#[derive(Debug)]
+pub enum MarketData {
+    Book(BookSnapshot),
+    PriceChange(BookChange),
+}
+
+#[derive(Debug)]
+pub struct BookLevel {
+    pub price: Decimal,
+    pub size: Decimal,
+}
+
+#[derive(Debug)]
+pub struct BookSnapshot {
+    pub bids: Vec<BookLevel>,
+    pub asks: Vec<BookLevel>,
+}
+
+#[derive(Debug)]
+pub struct BookChange {
+    pub side: OrderSide,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub best_bid: Option<Decimal>,
+    pub best_ask: Option<Decimal>,
 }
 
 #[derive(Debug)]
@@ -37,9 +66,53 @@ pub struct ChannelMessage {
     pub market_id: B256,
     pub channel_data: ChannelData,
 }
+#[derive(Debug)]
+pub enum ManagerMessage {
+    Market(ChannelMessage),
+    Reload(ConfigReload),
+}
+
+impl From<ChannelMessage> for ManagerMessage {
+    fn from(channel_message: ChannelMessage) -> Self {
+        Self::Market(channel_message)
+    }
+}
+
 #[derive(Debug)]
 pub enum ChannelData {
     UserData(UserData),
     MarketData(MarketData),
+    SnapshotFailed,
     OrderActionError,
+    OrderRejected(RejectedOrder),
+    Tick,
+    Allocation(Allocation),
+    Lifecycle(LifecycleEvent),
+}
+
+#[derive(Debug)]
+pub enum LifecycleEvent {
+    Resolved,
+    Closed,
+    TradingPaused,
+    TradingResumed,
+    TickSizeChanged(Decimal),
+    LastTradePrice(Decimal),
+}
+
+#[derive(Debug)]
+pub enum Allocation {
+    Add(Box<Market>),
+    Resize {
+        order_size: Decimal,
+        max_exposure: Decimal,
+    },
+    Retire,
+}
+
+#[derive(Debug)]
+pub struct ConfigReload {
+    pub markets: Vec<(B256, Market)>,
+    pub groups: Vec<GroupConfig>,
+    pub flatten_removed: bool,
 }
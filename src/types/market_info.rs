@@ -0,0 +1,18 @@
+use crate::types::TokenIds;
+use alloy::primitives::B256;
+use chrono::{DateTime, Utc};
+use polymarket_client_sdk::types::Decimal;
+
+pub struct MarketInfo {
+    pub slug: String,
+    pub event_slug: Option<String>,
+    pub token_ids: TokenIds,
+    pub condition_id: B256,
+    pub event_start: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub closed: bool,
+    pub accepting_orders: bool,
+    pub neg_risk: bool,
+    pub tick_size: Option<Decimal>,
+    pub min_order_size: Option<Decimal>,
+}
@@ -1,5 +1,6 @@
 use alloy::primitives::U256;
 
+#[derive(Debug, PartialEq)]
 pub struct TokenIds {
     pub buy_token: U256,
     pub sell_token: U256,
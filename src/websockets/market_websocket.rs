@@ -1,44 +1,133 @@
-use crate::types::channel_types::MarketData;
-use crate::types::channel_types::{ChannelData, ChannelMessage};
-use alloy::primitives::U256;
+use crate::types::channel_types::{
+    BookChange, BookLevel, BookSnapshot, ChannelData, ChannelMessage, LifecycleEvent,
+    ManagerMessage, MarketData, OrderSide,
+};
+use alloy::primitives::{B256, U256};
+use anyhow::Result;
 use futures::StreamExt;
+use polymarket_client_sdk::clob::types::Side;
 use polymarket_client_sdk::clob::ws::Client;
+use polymarket_client_sdk::clob::ws::types::response::{BookUpdate, OrderBookLevel, PriceChange};
 
-pub async fn connect_to_market_ws(tx: crossfire::MAsyncTx<ChannelMessage>, asset_ids: Vec<U256>) {
-    let client = Client::default();
-    let stream_result = client.subscribe_prices(asset_ids.clone());
+pub async fn connect_to_market_ws(tx: crossfire::MAsyncTx<ManagerMessage>, asset_ids: Vec<U256>) {
+    if let Err(e) = stream_market_data(&tx, &asset_ids).await {
+        eprintln!("Error connecting to market_logic: {:?}", e);
+    }
+}
 
-    match stream_result {
-        Ok(stream) => {
-            let mut stream = Box::pin(stream);
-            while let Some(price_change) = stream.next().await {
-                match price_change {
-                    Ok(price) => {
-                        for price_change in &price.price_changes {
-                            let asset_id = price_change.asset_id;
-                            if !asset_ids.contains(&asset_id) {
-                                continue;
-                            }
-                            let best_bid = price_change.best_bid.unwrap();
-                            let best_ask = price_change.best_ask.unwrap();
-                            let market_id = price.market;
+async fn stream_market_data(
+    tx: &crossfire::MAsyncTx<ManagerMessage>,
+    asset_ids: &[U256],
+) -> Result<()> {
+    let client = Client::default();
+    let mut book_stream = Box::pin(client.subscribe_orderbook(asset_ids.to_vec())?);
+    let mut price_stream = Box::pin(client.subscribe_prices(asset_ids.to_vec())?);
+    let mut tick_size_stream = Box::pin(client.subscribe_tick_size_changes(asset_ids.to_vec())?);
+    let mut last_trade_stream = Box::pin(client.subscribe_last_trade_prices(asset_ids.to_vec())?);
+    let mut resolution_stream = Box::pin(client.subscribe_market_resolutions(asset_ids.to_vec())?);
 
-                            let market_data = MarketData { best_bid, best_ask };
-                            let channel_message = ChannelMessage {
-                                market_id,
-                                channel_data: ChannelData::MarketData(market_data),
-                            };
-                            tx.send(channel_message).await.unwrap();
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("Error getting price change: {:?}", e);
-                    }
+    loop {
+        let channel_messages = tokio::select! {
+            Some(book_update) = book_stream.next() => match book_update {
+                Ok(book) => book_messages(book, asset_ids),
+                Err(e) => {
+                    eprintln!("Error getting book update: {:?}", e);
+                    continue;
+                }
+            },
+            Some(price_change) = price_stream.next() => match price_change {
+                Ok(price) => price_change_messages(price, asset_ids),
+                Err(e) => {
+                    eprintln!("Error getting price change: {:?}", e);
+                    continue;
                 }
-            }
+            },
+            Some(tick_size_change) = tick_size_stream.next() => match tick_size_change {
+                Ok(change) if asset_ids.contains(&change.asset_id) => vec![lifecycle_message(
+                    change.market,
+                    LifecycleEvent::TickSizeChanged(change.new_tick_size),
+                )],
+                Ok(_) => continue,
+                Err(e) => {
+                    eprintln!("Error getting tick size change: {:?}", e);
+                    continue;
+                }
+            },
+            Some(last_trade) = last_trade_stream.next() => match last_trade {
+                Ok(trade) if asset_ids.contains(&trade.asset_id) => vec![lifecycle_message(
+                    trade.market,
+                    LifecycleEvent::LastTradePrice(trade.price),
+                )],
+                Ok(_) => continue,
+                Err(e) => {
+                    eprintln!("Error getting last trade price: {:?}", e);
+                    continue;
+                }
+            },
+            Some(resolution) = resolution_stream.next() => match resolution {
+                Ok(resolved) => vec![lifecycle_message(resolved.market, LifecycleEvent::Resolved)],
+                Err(e) => {
+                    eprintln!("Error getting market resolution: {:?}", e);
+                    continue;
+                }
+            },
+            else => return Ok(()),
+        };
+        for channel_message in channel_messages {
+            tx.send(channel_message.into()).await.unwrap();
         }
-        Err(e) => {
-            eprintln!("Error connecting to market_logic: {:?}", e);
+    }
+}
+
+fn lifecycle_message(market_id: B256, lifecycle_event: LifecycleEvent) -> ChannelMessage {
+    ChannelMessage {
+        market_id,
+        channel_data: ChannelData::Lifecycle(lifecycle_event),
+    }
+}
+
+fn book_messages(book: BookUpdate, asset_ids: &[U256]) -> Vec<ChannelMessage> {
+    if !asset_ids.contains(&book.asset_id) {
+        return Vec::new();
+    }
+    let snapshot = BookSnapshot {
+        bids: book.bids.iter().map(book_level).collect(),
+        asks: book.asks.iter().map(book_level).collect(),
+    };
+    vec![ChannelMessage {
+        market_id: book.market,
+        channel_data: ChannelData::MarketData(MarketData::Book(snapshot)),
+    }]
+}
+
+fn price_change_messages(price: PriceChange, asset_ids: &[U256]) -> Vec<ChannelMessage> {
+    let mut channel_messages = Vec::new();
+    for price_change in price.price_changes {
+        if !asset_ids.contains(&price_change.asset_id) {
+            continue;
         }
+        let side = match price_change.side {
+            Side::Buy => OrderSide::Buy,
+            _ => OrderSide::Sell,
+        };
+        let book_change = BookChange {
+            side,
+            price: price_change.price,
+            size: price_change.size,
+            best_bid: price_change.best_bid,
+            best_ask: price_change.best_ask,
+        };
+        channel_messages.push(ChannelMessage {
+            market_id: price.market,
+            channel_data: ChannelData::MarketData(MarketData::PriceChange(book_change)),
+        });
+    }
+    channel_messages
+}
+
+fn book_level(level: &OrderBookLevel) -> BookLevel {
+    BookLevel {
+        price: level.price,
+        size: level.size,
     }
 }
@@ -1,5 +1,5 @@
 use crate::types::channel_types::{
-    ChannelData, ChannelMessage, OrderUpdate, PlacedOrder, UserData,
+    ChannelData, ChannelMessage, ManagerMessage, OrderUpdate, PlacedOrder, UserData,
 };
 use alloy::primitives::Address;
 use futures::StreamExt;
@@ -8,7 +8,7 @@ use polymarket_client_sdk::clob::ws::types::response::OrderMessageType;
 use polymarket_client_sdk::clob::ws::{Client, WsMessage};
 
 pub async fn connect_to_user_ws(
-    tx: crossfire::MAsyncTx<ChannelMessage>,
+    tx: crossfire::MAsyncTx<ManagerMessage>,
     credentials: Credentials,
     address: Address,
 ) {
@@ -50,7 +50,7 @@ pub async fn connect_to_user_ws(
                     market_id,
                     channel_data,
                 };
-                tx.send(channel_message).await.unwrap();
+                tx.send(channel_message.into()).await.unwrap();
             }
             Ok(WsMessage::Trade(trade)) => {
                 println!("{:?}", trade);